@@ -53,7 +53,8 @@ defmodule CodingAgent.Session.WasmBridge do
               wasm_tools =
                 ToolFactory.build_inventory(sidecar_pid, discover.tools,
                   cwd: cwd,
-                  session_id: session_id
+                  session_id: session_id,
+                  chain_id: wasm_config.chain_id
                 )
 
               wasm_tool_names = Enum.map(wasm_tools, &elem(&1, 0))
@@ -116,7 +117,8 @@ defmodule CodingAgent.Session.WasmBridge do
             wasm_tools =
               ToolFactory.build_inventory(state.wasm_sidecar_pid, discover.tools,
                 cwd: state.cwd,
-                session_id: state.session_manager.header.id
+                session_id: state.session_manager.header.id,
+                chain_id: WasmConfig.load(state.cwd, state.settings_manager).chain_id
               )
 
             wasm_tool_names = Enum.map(wasm_tools, &elem(&1, 0))
@@ -21,7 +21,15 @@ defmodule CodingAgent.Wasm.Config do
           default_fuel_limit: non_neg_integer(),
           cache_compiled: boolean(),
           cache_dir: String.t() | nil,
-          max_tool_invoke_depth: pos_integer()
+          max_tool_invoke_depth: pos_integer(),
+          max_resident_components: pos_integer() | nil,
+          max_host_calls: pos_integer() | nil,
+          max_events_per_invoke: pos_integer() | nil,
+          offline: boolean(),
+          allow_unredacted_debug: boolean(),
+          forbid_binary_output: boolean(),
+          rng_seed: non_neg_integer() | nil,
+          chain_id: String.t() | nil
         }
 
   defstruct enabled: false,
@@ -34,7 +42,15 @@ defmodule CodingAgent.Wasm.Config do
             default_fuel_limit: @default_fuel_limit,
             cache_compiled: true,
             cache_dir: nil,
-            max_tool_invoke_depth: @default_max_depth
+            max_tool_invoke_depth: @default_max_depth,
+            max_resident_components: nil,
+            max_host_calls: nil,
+            max_events_per_invoke: nil,
+            offline: false,
+            allow_unredacted_debug: false,
+            forbid_binary_output: false,
+            rng_seed: nil,
+            chain_id: nil
 
   @spec load(String.t(), map() | nil) :: t()
   def load(cwd, settings_manager \\ nil) do
@@ -76,7 +92,15 @@ defmodule CodingAgent.Wasm.Config do
       cache_compiled: parse_boolean(wasm["cache_compiled"], true),
       cache_dir: cache_dir,
       max_tool_invoke_depth:
-        parse_positive_integer(wasm["max_tool_invoke_depth"], @default_max_depth)
+        parse_positive_integer(wasm["max_tool_invoke_depth"], @default_max_depth),
+      max_resident_components: parse_positive_integer(wasm["max_resident_components"], nil),
+      max_host_calls: parse_positive_integer(wasm["max_host_calls"], nil),
+      max_events_per_invoke: parse_positive_integer(wasm["max_events_per_invoke"], nil),
+      offline: parse_boolean(wasm["offline"], false),
+      allow_unredacted_debug: parse_boolean(wasm["allow_unredacted_debug"], false),
+      forbid_binary_output: parse_boolean(wasm["forbid_binary_output"], false),
+      rng_seed: parse_non_negative_integer(wasm["rng_seed"], nil),
+      chain_id: parse_chain_id(wasm["chain_id"])
     }
   end
 
@@ -108,6 +132,30 @@ defmodule CodingAgent.Wasm.Config do
 
   defp parse_positive_integer(_value, default), do: default
 
+  defp parse_non_negative_integer(value, _default) when is_integer(value) and value >= 0,
+    do: value
+
+  defp parse_non_negative_integer(value, default) when is_binary(value) do
+    case Integer.parse(String.trim(value)) do
+      {parsed, ""} when parsed >= 0 -> parsed
+      _ -> default
+    end
+  end
+
+  defp parse_non_negative_integer(_value, default), do: default
+
+  # A chain ID (8453) or a Foundry chain name ("sepolia").
+  defp parse_chain_id(value) when is_integer(value) and value > 0, do: Integer.to_string(value)
+
+  defp parse_chain_id(value) when is_binary(value) do
+    case String.trim(value) do
+      "" -> nil
+      trimmed -> trimmed
+    end
+  end
+
+  defp parse_chain_id(_), do: nil
+
   defp parse_optional_path(nil, _cwd), do: nil
 
   defp parse_optional_path(path, cwd) when is_binary(path) do
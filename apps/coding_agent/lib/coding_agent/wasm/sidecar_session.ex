@@ -20,6 +20,8 @@ defmodule CodingAgent.Wasm.SidecarSession do
           schema_json: String.t(),
           capabilities: map(),
           auth: map() | nil,
+          effects: :pure | :readonly | :mutating,
+          tags: [String.t()],
           warnings: [String.t()]
         }
 
@@ -30,6 +32,9 @@ defmodule CodingAgent.Wasm.SidecarSession do
         }
 
   @type invoke_result :: %{
+          optional(:unredacted_debug) => [map()],
+          optional(:warnings) => [String.t()],
+          optional(:error_kind) => :tool | :runtime,
           output_json: String.t() | nil,
           error: String.t() | nil,
           logs: [map()],
@@ -184,7 +189,14 @@ defmodule CodingAgent.Wasm.SidecarSession do
           "default_fuel_limit" => state.config.default_fuel_limit,
           "cache_compiled" => state.config.cache_compiled,
           "cache_dir" => state.config.cache_dir,
-          "max_tool_invoke_depth" => state.config.max_tool_invoke_depth
+          "max_tool_invoke_depth" => state.config.max_tool_invoke_depth,
+          "max_resident_components" => state.config.max_resident_components,
+          "max_host_calls" => state.config.max_host_calls,
+          "max_events_per_invoke" => state.config.max_events_per_invoke,
+          "offline" => state.config.offline,
+          "allow_unredacted_debug" => state.config.allow_unredacted_debug,
+          "forbid_binary_output" => state.config.forbid_binary_output,
+          "rng_seed" => state.config.rng_seed
         }
       }
 
@@ -495,6 +507,8 @@ defmodule CodingAgent.Wasm.SidecarSession do
           schema_json: to_string(tool["schema_json"] || "{}"),
           capabilities: normalize_capabilities(tool["capabilities"] || %{}),
           auth: normalize_auth_metadata(tool["auth"]),
+          effects: normalize_effects(tool["effects"]),
+          tags: normalize_string_list(tool["tags"] || []),
           warnings: normalize_string_list(tool["warnings"] || [])
         }
       end)
@@ -511,12 +525,33 @@ defmodule CodingAgent.Wasm.SidecarSession do
     do: %{tools: [], warnings: [], errors: ["invalid_discover_result"]}
 
   defp normalize_invoke_result(result) when is_map(result) do
-    %{
+    normalized = %{
       output_json: normalize_optional_string(result["output_json"]),
       error: normalize_optional_string(result["error"]),
       logs: normalize_logs(result["logs"] || []),
       details: result["details"] || %{}
     }
+
+    # Pre-redaction outputs; only sent when allow_unredacted_debug is on.
+    normalized =
+      case result["unredacted_debug"] do
+        entries when is_list(entries) -> Map.put(normalized, :unredacted_debug, entries)
+        _ -> normalized
+      end
+
+    # Stderr warnings from successful exec calls; omitted when there are none.
+    normalized =
+      case result["warnings"] do
+        [_ | _] = warnings -> Map.put(normalized, :warnings, Enum.filter(warnings, &is_binary/1))
+        _ -> normalized
+      end
+
+    # Whether `error` came from the tool itself or from the runtime stopping it.
+    case result["error_kind"] do
+      "tool" -> Map.put(normalized, :error_kind, :tool)
+      "runtime" -> Map.put(normalized, :error_kind, :runtime)
+      _ -> normalized
+    end
   end
 
   defp normalize_invoke_result(_),
@@ -535,7 +570,9 @@ defmodule CodingAgent.Wasm.SidecarSession do
       tool_invoke: truthy?(capabilities["tool_invoke"] || capabilities[:tool_invoke]),
       secrets: truthy?(capabilities["secrets"] || capabilities[:secrets]),
       auth: truthy?(capabilities["auth"] || capabilities[:auth]),
-      exec: truthy?(capabilities["exec"] || capabilities[:exec])
+      exec: truthy?(capabilities["exec"] || capabilities[:exec]),
+      exec_idempotent:
+        truthy?(capabilities["exec_idempotent"] || capabilities[:exec_idempotent])
     }
   end
 
@@ -546,9 +583,15 @@ defmodule CodingAgent.Wasm.SidecarSession do
       tool_invoke: false,
       secrets: false,
       auth: false,
-      exec: false
+      exec: false,
+      exec_idempotent: false
     }
 
+  # Anything but an explicit pure/readonly declaration is treated as mutating.
+  defp normalize_effects("pure"), do: :pure
+  defp normalize_effects("readonly"), do: :readonly
+  defp normalize_effects(_), do: :mutating
+
   defp normalize_auth_metadata(nil), do: nil
 
   defp normalize_auth_metadata(auth) when is_map(auth) do
@@ -3,6 +3,8 @@ defmodule CodingAgent.Wasm.ToolFactory do
   Builds `AgentTool` wrappers for discovered WASM tools.
   """
 
+  require Logger
+
   alias AgentCore.Types.{AgentTool, AgentToolResult}
   alias Ai.Types.TextContent
   alias CodingAgent.Wasm.SidecarSession
@@ -15,6 +17,13 @@ defmodule CodingAgent.Wasm.ToolFactory do
     cwd = Keyword.get(opts, :cwd, ".")
     session_id = Keyword.get(opts, :session_id, "")
 
+    # Tools fall back to the session's chain when a call doesn't name one.
+    context =
+      case Keyword.get(opts, :chain_id) do
+        nil -> %{cwd: cwd, session_id: session_id}
+        chain_id -> %{cwd: cwd, session_id: session_id, chain_id: chain_id}
+      end
+
     Enum.map(discovered_tools, fn tool ->
       name = tool.name
       description = tool.description
@@ -26,12 +35,14 @@ defmodule CodingAgent.Wasm.ToolFactory do
         warnings: tool.warnings,
         capabilities: tool.capabilities,
         auth: tool.auth,
+        effects: Map.get(tool, :effects, :mutating),
+        tags: Map.get(tool, :tags, []),
         source: :wasm
       }
 
       execute =
         build_execute_fun(sidecar_pid, name,
-          context_json: Jason.encode!(%{cwd: cwd, session_id: session_id}),
+          context_json: Jason.encode!(context),
           metadata: metadata
         )
 
@@ -135,8 +146,19 @@ defmodule CodingAgent.Wasm.ToolFactory do
   defp reason_type(reason), do: reason |> :erlang.term_to_binary() |> hash_value()
 
   defp build_success_result(name, invoke_result, metadata) do
+    # Pre-redaction outputs hold live secrets, so they only go to the debug
+    # log and never into `details`, which is streamed and persisted.
+    {unredacted_debug, invoke_result} = Map.pop(invoke_result, :unredacted_debug)
+
+    if unredacted_debug do
+      Logger.debug("WASM tool '#{name}' unredacted debug output: #{inspect(unredacted_debug)}")
+    end
+
     text =
       cond do
+        Map.get(invoke_result, :error_kind) == :runtime ->
+          "WASM tool '#{name}' was stopped by the runtime: #{invoke_result.error}"
+
         is_binary(invoke_result.error) and invoke_result.error != "" ->
           "WASM tool '#{name}' returned an error: #{invoke_result.error}"
 
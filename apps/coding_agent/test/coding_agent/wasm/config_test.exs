@@ -216,6 +216,68 @@ defmodule CodingAgent.Wasm.ConfigTest do
       assert config.max_tool_invoke_depth == 1
     end
 
+    test "max_resident_components is unset by default and parses positive integers" do
+      assert Config.load(".", %{}).max_resident_components == nil
+
+      config = Config.load(".", %{tools: %{wasm: %{max_resident_components: "16"}}})
+      assert config.max_resident_components == 16
+
+      config = Config.load(".", %{tools: %{wasm: %{max_resident_components: 0}}})
+      assert config.max_resident_components == 1
+    end
+
+    test "max_host_calls is unset by default and parses positive integers" do
+      assert Config.load(".", %{}).max_host_calls == nil
+
+      config = Config.load(".", %{tools: %{wasm: %{max_host_calls: "40"}}})
+      assert config.max_host_calls == 40
+    end
+
+    test "max_events_per_invoke is unset by default and parses positive integers" do
+      assert Config.load(".", %{}).max_events_per_invoke == nil
+
+      config = Config.load(".", %{tools: %{wasm: %{max_events_per_invoke: "200"}}})
+      assert config.max_events_per_invoke == 200
+    end
+
+    test "offline defaults to false and parses boolean" do
+      assert Config.load(".", %{}).offline == false
+
+      config = Config.load(".", %{tools: %{wasm: %{offline: "true"}}})
+      assert config.offline == true
+    end
+
+    test "allow_unredacted_debug defaults to false and parses boolean" do
+      assert Config.load(".", %{}).allow_unredacted_debug == false
+
+      config = Config.load(".", %{tools: %{wasm: %{allow_unredacted_debug: true}}})
+      assert config.allow_unredacted_debug == true
+    end
+
+    test "forbid_binary_output defaults to false and parses boolean" do
+      assert Config.load(".", %{}).forbid_binary_output == false
+
+      config = Config.load(".", %{tools: %{wasm: %{forbid_binary_output: true}}})
+      assert config.forbid_binary_output == true
+    end
+
+    test "rng_seed is unset by default and parses non-negative integers" do
+      assert Config.load(".", %{}).rng_seed == nil
+
+      assert Config.load(".", %{tools: %{wasm: %{rng_seed: "0"}}}).rng_seed == 0
+      assert Config.load(".", %{tools: %{wasm: %{rng_seed: 42}}}).rng_seed == 42
+      assert Config.load(".", %{tools: %{wasm: %{rng_seed: -1}}}).rng_seed == nil
+    end
+
+    test "chain_id is unset by default and accepts an ID or chain name" do
+      assert Config.load(".", %{}).chain_id == nil
+
+      assert Config.load(".", %{tools: %{wasm: %{chain_id: 8453}}}).chain_id == "8453"
+      assert Config.load(".", %{tools: %{wasm: %{chain_id: " sepolia "}}}).chain_id == "sepolia"
+      assert Config.load(".", %{tools: %{wasm: %{chain_id: ""}}}).chain_id == nil
+      assert Config.load(".", %{tools: %{wasm: %{chain_id: 0}}}).chain_id == nil
+    end
+
     test "handles string keys in settings map" do
       config = Config.load(".", %{"tools" => %{"wasm" => %{"enabled" => true}}})
       assert config.enabled == true
@@ -335,5 +335,105 @@ defmodule CodingAgent.Wasm.ToolFactoryTest do
 
       :telemetry.detach(telemetry_ref)
     end
+
+    test "labels runtime errors separately from tool errors" do
+      discovered = [
+        %{
+          name: "stopped_wasm",
+          description: "Stopped",
+          schema_json: "{}",
+          path: "/tools/stopped.wasm",
+          warnings: [],
+          capabilities: %{},
+          auth: nil
+        }
+      ]
+
+      for {kind, expected} <- [
+            tool: "WASM tool 'stopped_wasm' returned an error: boom",
+            runtime: "WASM tool 'stopped_wasm' was stopped by the runtime: boom"
+          ] do
+        {:ok, sidecar} =
+          FakeSidecar.start(
+            owner: self(),
+            response:
+              {:ok,
+               %{output_json: nil, error: "boom", error_kind: kind, logs: [], details: %{}}}
+          )
+
+        [{_name, tool, _source}] = ToolFactory.build_inventory(sidecar, discovered)
+        result = tool.execute.("call-id", %{}, nil, nil)
+
+        assert [%{text: ^expected}] = result.content
+      end
+    end
+
+    test "sends the configured chain_id in the invoke context" do
+      discovered = [
+        %{
+          name: "chain_wasm",
+          description: "Chain",
+          schema_json: "{}",
+          path: "/tools/chain.wasm",
+          warnings: [],
+          capabilities: %{},
+          auth: nil
+        }
+      ]
+
+      {:ok, sidecar} =
+        FakeSidecar.start(
+          owner: self(),
+          response: {:ok, %{output_json: "null", error: nil, logs: [], details: %{}}}
+        )
+
+      for {opts, expected} <- [
+            {[chain_id: "8453"], %{"cwd" => "/p", "session_id" => "s", "chain_id" => "8453"}},
+            {[], %{"cwd" => "/p", "session_id" => "s"}}
+          ] do
+        [{_name, tool, _source}] =
+          ToolFactory.build_inventory(sidecar, discovered, [cwd: "/p", session_id: "s"] ++ opts)
+
+        tool.execute.("call-id", %{}, nil, nil)
+
+        assert_receive {:fake_wasm_invoke, "chain_wasm", _params, context_json}
+        assert Jason.decode!(context_json) == expected
+      end
+    end
+
+    test "never puts unredacted debug output in the result details" do
+      discovered = [
+        %{
+          name: "debug_wasm",
+          description: "Debug",
+          schema_json: "{}",
+          path: "/tools/debug.wasm",
+          warnings: [],
+          capabilities: %{},
+          auth: nil
+        }
+      ]
+
+      {:ok, sidecar} =
+        FakeSidecar.start(
+          owner: self(),
+          response:
+            {:ok,
+             %{
+               output_json: ~s("[REDACTED]"),
+               error: nil,
+               logs: [],
+               details: %{},
+               unredacted_debug: [%{"host_call" => "exec_command", "stdout" => "s3cret"}]
+             }}
+        )
+
+      [{_name, tool, _source}] = ToolFactory.build_inventory(sidecar, discovered)
+      result = tool.execute.("call-id", %{}, nil, nil)
+
+      assert [%{text: "[REDACTED]"}] = result.content
+      refute Map.has_key?(result.details.invoke, :unredacted_debug)
+      refute inspect(result.details) =~ "s3cret"
+    end
   end
 end
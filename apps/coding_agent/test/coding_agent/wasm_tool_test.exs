@@ -43,6 +43,8 @@ defmodule CodingAgent.WasmToolTest do
             "schema_json": json.dumps({"type": "object", "properties": {"text": {"type": "string"}}, "required": ["text"]}),
             "capabilities": {"workspace_read": False, "http": False, "tool_invoke": False, "secrets": False},
             "auth": {"secret_name": "echo_token", "display_name": "Echo", "has_oauth": False},
+            "effects": "pure",
+            "tags": ["text", "read-only"],
             "warnings": []
           },
           {
@@ -125,6 +127,8 @@ defmodule CodingAgent.WasmToolTest do
     assert text =~ "echo"
     assert source_meta.auth.secret_name == "echo_token"
     assert source_meta.capabilities.auth == false
+    assert source_meta.effects == :pure
+    assert source_meta.tags == ["text", "read-only"]
   end
 
   test "wasm tool error payload is converted to text", %{tmp_dir: tmp_dir} do
@@ -136,9 +140,13 @@ defmodule CodingAgent.WasmToolTest do
     inventory =
       ToolFactory.build_inventory(sidecar, discover.tools, cwd: tmp_dir, session_id: "s1")
 
-    {"error_wasm", tool, _source} =
+    {"error_wasm", tool, {:wasm, source_meta}} =
       Enum.find(inventory, fn {name, _, _} -> name == "error_wasm" end)
 
+    # No `effects` in the discover payload, so it defaults to mutating.
+    assert source_meta.effects == :mutating
+    assert source_meta.tags == []
+
     result = tool.execute.("call-2", %{}, nil, nil)
 
     assert %AgentToolResult{} = result
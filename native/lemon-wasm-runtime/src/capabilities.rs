@@ -1,11 +1,35 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use url::Url;
 
-use crate::protocol::ToolCapabilitiesSummary;
+use crate::protocol::{CapabilitiesValidation, ToolCapabilitiesSummary};
+
+pub const DEFAULT_WORKSPACE_MAX_READ_BYTES: usize = 5 * 1024 * 1024;
+
+/// `"program subcommand"` pairs a `read_only` tool is denied unless the
+/// file sets its own `mutating_commands`. Foundry's visible aliases are
+/// listed alongside the commands they stand for.
+pub const DEFAULT_MUTATING_COMMANDS: [&str; 9] = [
+    "cast send",
+    "cast s",
+    "cast publish",
+    "cast p",
+    "cast rpc",
+    "cast rp",
+    "forge create",
+    "forge c",
+    "forge script",
+];
+
+/// `cast` subcommands (with aliases) whose first argument is the target
+/// address that `allowed_targets` restricts.
+pub const TARGET_SUBCOMMANDS: [&str; 8] = ["call", "c", "send", "s", "mktx", "m", "estimate", "e"];
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CapabilitiesFile {
@@ -21,6 +45,28 @@ pub struct CapabilitiesFile {
     pub auth: Option<AuthCapabilitySchema>,
     #[serde(default)]
     pub exec: Option<ExecCapabilitySchema>,
+    /// Counts http, exec, and tool-invoke calls together.
+    #[serde(default)]
+    pub max_host_calls: Option<u32>,
+    #[serde(default)]
+    pub capture_stdio: bool,
+    #[serde(default)]
+    pub max_additional_fuel: Option<u64>,
+    #[serde(default)]
+    pub output_transform: Option<OutputTransformSchema>,
+    #[serde(default)]
+    pub network: Option<NetworkCapabilitySchema>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Merged under every invocation's params; the caller's values win.
+    #[serde(default)]
+    pub default_params: serde_json::Map<String, Value>,
+    /// Denies exec commands in `mutating_commands` and non-GET http requests
+    /// even if the allowlists permit them.
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub mutating_commands: Option<Vec<String>>,
 }
 
 impl CapabilitiesFile {
@@ -32,6 +78,183 @@ impl CapabilitiesFile {
         Ok(parsed)
     }
 
+    /// Parses `raw` and lints it; a parse failure is reported as an error
+    /// finding rather than failing the request.
+    pub fn validate_json(raw: &str) -> CapabilitiesValidation {
+        match serde_json::from_str::<Self>(raw) {
+            Ok(capabilities) => capabilities.validate(),
+            Err(err) => CapabilitiesValidation {
+                valid: false,
+                warnings: Vec::new(),
+                errors: vec![format!("failed to parse capabilities: {}", err)],
+            },
+        }
+    }
+
+    pub fn validate(&self) -> CapabilitiesValidation {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            warnings.push("tags contains an empty tag, which is ignored".to_string());
+        }
+
+        if let Some(http) = &self.http {
+            if !http.credentials.is_empty() && http.allowlist.is_empty() {
+                warnings.push(
+                    "http.credentials is set but http.allowlist is empty, so no request can use them"
+                        .to_string(),
+                );
+            }
+
+            for (key, mapping) in sorted(&http.credentials) {
+                if !self.secret_allowed(&mapping.secret_name) {
+                    errors.push(format!(
+                        "http.credentials.{}: secret '{}' is not allowed by secrets.allowed_names",
+                        key, mapping.secret_name
+                    ));
+                }
+                if let CredentialLocationSchema::BodyField { json_pointer } = &mapping.location
+                    && !json_pointer.starts_with('/')
+                {
+                    errors.push(format!(
+                        "http.credentials.{}: json_pointer '{}' must start with '/'",
+                        key, json_pointer
+                    ));
+                }
+            }
+
+            for (index, endpoint) in http.allowlist.iter().enumerate() {
+                if endpoint.cost == Some(0) {
+                    warnings.push(format!(
+                        "http.allowlist[{}]: cost 0 makes requests to '{}' free of rate limits",
+                        index, endpoint.host
+                    ));
+                }
+            }
+
+            let timeout_secs = http.timeout_secs.unwrap_or(30);
+            if let Some(connect_timeout_secs) = http.connect_timeout_secs
+                && connect_timeout_secs >= timeout_secs
+            {
+                warnings.push(format!(
+                    "http.connect_timeout_secs ({}) is not below the request timeout ({}s), so it has no effect",
+                    connect_timeout_secs, timeout_secs
+                ));
+            }
+
+            lint_rate_limit("http", http.rate_limit.as_ref(), &mut warnings);
+        }
+
+        if let Some(exec) = &self.exec {
+            if !exec.credentials.is_empty() && exec.allowlist.is_empty() {
+                warnings.push(
+                    "exec.credentials is set but exec.allowlist is empty, so no command can use them"
+                        .to_string(),
+                );
+            }
+
+            for (key, mapping) in sorted(&exec.credentials) {
+                if !self.secret_allowed(&mapping.secret_name) {
+                    errors.push(format!(
+                        "exec.credentials.{}: secret '{}' is not allowed by secrets.allowed_names",
+                        key, mapping.secret_name
+                    ));
+                }
+                if let Some(format) = &mapping.format
+                    && secret_format_regex(format).is_err()
+                {
+                    errors.push(format!(
+                        "exec.credentials.{}: format '{}' is not a valid regex",
+                        key, format
+                    ));
+                }
+            }
+
+            for (index, entry) in exec.allowlist.iter().enumerate() {
+                if entry.program.trim().is_empty() {
+                    errors.push(format!("exec.allowlist[{}]: program is empty", index));
+                }
+                if entry.cost == Some(0) {
+                    warnings.push(format!(
+                        "exec.allowlist[{}]: cost 0 makes '{}' free of rate limits",
+                        index, entry.program
+                    ));
+                }
+            }
+
+            lint_rate_limit("exec", exec.rate_limit.as_ref(), &mut warnings);
+        }
+
+        if let Some(tool_invoke) = &self.tool_invoke {
+            if tool_invoke.aliases.is_empty() {
+                warnings.push(
+                    "tool_invoke has no aliases, so the tool can't invoke anything".to_string(),
+                );
+            }
+            lint_rate_limit(
+                "tool_invoke",
+                tool_invoke.rate_limit.as_ref(),
+                &mut warnings,
+            );
+        }
+
+        if let Some(auth) = &self.auth {
+            if auth.oauth.is_some()
+                && !self.http.as_ref().is_some_and(|http| {
+                    http.credentials
+                        .values()
+                        .any(|mapping| mapping.secret_name == auth.secret_name)
+                })
+            {
+                warnings.push(format!(
+                    "auth.oauth access tokens are only injected through an http.credentials mapping for '{}', and there is none",
+                    auth.secret_name
+                ));
+            }
+
+            if let Some(endpoint) = &auth.validation_endpoint
+                && let Err(err) = Url::parse(&endpoint.url)
+            {
+                errors.push(format!(
+                    "auth.validation_endpoint.url '{}' is not a valid URL: {}",
+                    endpoint.url, err
+                ));
+            }
+        }
+
+        if self
+            .network
+            .as_ref()
+            .is_some_and(|network| network.rpc_allowlist.is_empty())
+        {
+            warnings
+                .push("network.rpc_allowlist is empty, so RPC URLs are not restricted".to_string());
+        }
+
+        if let Some(extract) = self
+            .output_transform
+            .as_ref()
+            .and_then(|transform| transform.extract.as_deref())
+            && !extract.starts_with('/')
+        {
+            errors.push(format!(
+                "output_transform.extract '{}' must start with '/'",
+                extract
+            ));
+        }
+
+        if self.max_host_calls == Some(0) {
+            errors.push("max_host_calls is 0, so every host call is refused".to_string());
+        }
+
+        CapabilitiesValidation {
+            valid: errors.is_empty(),
+            warnings,
+            errors,
+        }
+    }
+
     pub fn summary(&self) -> ToolCapabilitiesSummary {
         ToolCapabilitiesSummary {
             workspace_read: self.workspace.is_some(),
@@ -40,6 +263,7 @@ impl CapabilitiesFile {
             secrets: self.secrets.is_some(),
             auth: self.auth.is_some(),
             exec: self.exec.is_some(),
+            exec_idempotent: self.exec_idempotent(),
         }
     }
 
@@ -73,6 +297,13 @@ impl CapabilitiesFile {
         }
     }
 
+    pub fn workspace_max_read_bytes(&self) -> usize {
+        self.workspace
+            .as_ref()
+            .and_then(|workspace| workspace.max_read_bytes)
+            .unwrap_or(DEFAULT_WORKSPACE_MAX_READ_BYTES)
+    }
+
     pub fn resolve_tool_alias(&self, alias: &str) -> Option<String> {
         self.tool_invoke
             .as_ref()
@@ -97,25 +328,15 @@ impl CapabilitiesFile {
             .unwrap_or(50)
     }
 
-    pub fn http_allowed(&self, method: &str, url: &str) -> bool {
-        let Some(http) = &self.http else {
-            return false;
-        };
-
-        let parsed = match Url::parse(url) {
-            Ok(parsed) => parsed,
-            Err(_) => return false,
-        };
-
-        let host = match parsed.host_str() {
-            Some(host) => host,
-            None => return false,
-        };
+    pub fn http_allowed(&self, method: &str, url: &str) -> Option<&EndpointPatternSchema> {
+        let http = self.http.as_ref()?;
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
 
         let path = parsed.path();
         let method = method.to_ascii_uppercase();
 
-        http.allowlist.iter().any(|pattern| {
+        http.allowlist.iter().find(|pattern| {
             host_matches_pattern(host, &pattern.host)
                 && pattern
                     .path_prefix
@@ -134,27 +355,66 @@ impl CapabilitiesFile {
         self.http.as_ref()
     }
 
+    /// Whether a response header is passed back to the tool. `set-cookie`
+    /// is dropped unless `http.response_header_allowlist` names it.
+    pub fn response_header_allowed(&self, name: &str) -> bool {
+        let allowlist = self
+            .http
+            .as_ref()
+            .and_then(|http| http.response_header_allowlist.as_ref());
+
+        match allowlist {
+            Some(allowed) => allowed
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(name)),
+            None => !name.eq_ignore_ascii_case("set-cookie"),
+        }
+    }
+
     pub fn auth_config(&self) -> Option<&AuthCapabilitySchema> {
         self.auth.as_ref()
     }
 
+    pub fn oauth_refresh(&self) -> Option<(&AuthCapabilitySchema, &OAuthConfigSchema, String)> {
+        let auth = self.auth.as_ref()?;
+        let oauth = auth.oauth.as_ref()?;
+        let refresh_secret = oauth
+            .refresh_token_secret
+            .clone()
+            .unwrap_or_else(|| format!("{}_REFRESH_TOKEN", auth.secret_name));
+        Some((auth, oauth, refresh_secret))
+    }
+
     pub fn exec_config(&self) -> Option<&ExecCapabilitySchema> {
         self.exec.as_ref()
     }
 
-    pub fn exec_allowed(&self, program: &str, args: &[String]) -> Result<(), String> {
-        let exec = self
-            .exec
-            .as_ref()
-            .ok_or_else(|| "exec capability not granted".to_string())?;
+    pub fn exec_idempotent(&self) -> bool {
+        self.exec.as_ref().is_some_and(|exec| exec.idempotent)
+    }
 
+    pub fn exec_allowed(&self, program: &str, args: &[String]) -> Result<(), CapabilityDenial> {
         let subcommand = args.first().map(String::as_str).unwrap_or("");
 
+        let exec = self.exec.as_ref().ok_or_else(|| {
+            CapabilityDenial::exec(
+                program,
+                subcommand,
+                "exec capability not granted".to_string(),
+            )
+        })?;
+
         let entry = exec
             .allowlist
             .iter()
             .find(|entry| entry.program == program)
-            .ok_or_else(|| format!("program '{}' not in exec allowlist", program))?;
+            .ok_or_else(|| {
+                CapabilityDenial::exec(
+                    program,
+                    subcommand,
+                    format!("program '{}' not in exec allowlist", program),
+                )
+            })?;
 
         if !entry.allowed_subcommands.is_empty()
             && !entry
@@ -162,21 +422,230 @@ impl CapabilitiesFile {
                 .iter()
                 .any(|allowed| allowed == subcommand)
         {
-            return Err(format!(
-                "subcommand '{}' not allowed for program '{}'",
-                subcommand, program
+            return Err(CapabilityDenial::exec(
+                program,
+                subcommand,
+                format!(
+                    "subcommand '{}' not allowed for program '{}'",
+                    subcommand, program
+                ),
             ));
         }
 
+        if !entry.allowed_targets.is_empty() && TARGET_SUBCOMMANDS.contains(&subcommand) {
+            let target = args.get(1).map(String::as_str).unwrap_or("");
+            let target_lower = target.to_ascii_lowercase();
+            if !entry
+                .allowed_targets
+                .iter()
+                .any(|allowed| match_pattern(&allowed.to_ascii_lowercase(), &target_lower))
+            {
+                return Err(CapabilityDenial::exec_target(program, subcommand, target));
+            }
+        }
+
+        self.read_only_exec_allowed(entry, args)?;
+
         for arg in args {
             if entry.blocked_flags.iter().any(|blocked| arg == blocked) {
-                return Err(format!("blocked flag '{}' for program '{}'", arg, program));
+                // No grant lifts a blocked flag, so there is nothing to suggest.
+                return Err(CapabilityDenial {
+                    capability: "exec",
+                    resource: program.to_string(),
+                    allow: None,
+                    message: format!("blocked flag '{}' for program '{}'", arg, program),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_only_exec_allowed(
+        &self,
+        entry: &ExecAllowlistEntry,
+        args: &[String],
+    ) -> Result<(), CapabilityDenial> {
+        if !self.read_only {
+            return Ok(());
+        }
+
+        let program = entry.program.as_str();
+        let mutating: Vec<&str> = match &self.mutating_commands {
+            Some(commands) => commands.iter().map(|listed| listed.trim()).collect(),
+            None => DEFAULT_MUTATING_COMMANDS.to_vec(),
+        };
+
+        let command = format!("{} {}", program, exec_subcommand(args));
+        if mutating.contains(&command.as_str()) {
+            return Err(CapabilityDenial::read_only(
+                program,
+                format!("tool is read_only; '{}' may change state", command),
+            ));
+        }
+
+        // A denylist can't keep up with every alias and flag a CLI accepts,
+        // so programs it covers must also be pinned to known subcommands.
+        let covered = mutating
+            .iter()
+            .any(|listed| listed.split_whitespace().next() == Some(program));
+        if covered && entry.allowed_subcommands.is_empty() {
+            return Err(CapabilityDenial::read_only(
+                program,
+                format!(
+                    "tool is read_only; the exec allowlist entry for '{}' must set allowed_subcommands",
+                    program
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn read_only_http_allowed(&self, method: &str, url: &str) -> Result<(), CapabilityDenial> {
+        if !self.read_only || method.eq_ignore_ascii_case("GET") {
+            return Ok(());
+        }
+
+        Err(CapabilityDenial::read_only(
+            url,
+            format!(
+                "tool is read_only; http {} requests are not allowed",
+                method.to_ascii_uppercase()
+            ),
+        ))
+    }
+
+    /// Checks an RPC URL passed to `cast`/`forge` against
+    /// `network.rpc_allowlist`. No list, or an empty one, allows any URL.
+    pub fn rpc_url_allowed(&self, url: &str) -> Result<(), CapabilityDenial> {
+        let Some(allowlist) = self
+            .network
+            .as_ref()
+            .map(|network| &network.rpc_allowlist)
+            .filter(|allowlist| !allowlist.is_empty())
+        else {
+            return Ok(());
+        };
+
+        if allowlist.iter().any(|allowed| match_pattern(allowed, url)) {
+            Ok(())
+        } else {
+            Err(CapabilityDenial::rpc_url(url))
+        }
+    }
+
+    pub fn rpc_url_required(&self, program: &str) -> Result<(), CapabilityDenial> {
+        if self
+            .network
+            .as_ref()
+            .is_none_or(|network| network.rpc_allowlist.is_empty())
+        {
+            return Ok(());
+        }
+
+        Err(CapabilityDenial {
+            capability: "network",
+            resource: program.to_string(),
+            allow: None,
+            message: format!(
+                "network.rpc_allowlist is set, so '{}' must name its RPC URL with --rpc-url",
+                program
+            ),
+        })
+    }
+
+    pub fn exec_cost(&self, program: &str) -> u32 {
+        self.exec
+            .as_ref()
+            .and_then(|exec| exec.allowlist.iter().find(|entry| entry.program == program))
+            .and_then(|entry| entry.cost)
+            .unwrap_or(1)
+    }
+
+    pub fn exec_file_credentials(&self) -> HashMap<String, String> {
+        self.exec
+            .iter()
+            .flat_map(|exec| exec.credentials.values())
+            .filter_map(|mapping| match &mapping.injection {
+                ExecCredentialInjection::File { path_template } => {
+                    Some((mapping.secret_name.clone(), path_template.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn check_secret_format(&self, name: &str, value: &str) -> Result<(), String> {
+        let Some(exec) = &self.exec else {
+            return Ok(());
+        };
+
+        for mapping in exec.credentials.values() {
+            if mapping.secret_name != name {
+                continue;
+            }
+            let Some(format) = &mapping.format else {
+                continue;
+            };
+            let regex = secret_format_regex(format)
+                .map_err(|_| format!("secret '{}' has an invalid format regex", name))?;
+            if !regex.is_match(value) {
+                return Err(format!("secret '{}' does not match expected format", name));
             }
         }
 
         Ok(())
     }
 
+    pub fn exec_env_passthrough(&self) -> Option<Vec<String>> {
+        let patterns = self.exec.as_ref()?.env_passthrough.as_ref()?;
+        Some(
+            std::iter::once("PATH".to_string())
+                .chain(patterns.iter().cloned())
+                .collect(),
+        )
+    }
+
+    pub fn exec_warning_patterns(&self, program: &str) -> &[String] {
+        self.exec
+            .as_ref()
+            .and_then(|exec| exec.allowlist.iter().find(|entry| entry.program == program))
+            .map(|entry| entry.warning_patterns.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn nonce_management_allowed(&self) -> bool {
+        self.exec
+            .as_ref()
+            .map(|exec| exec.nonce_management)
+            .unwrap_or(false)
+    }
+
+    pub fn min_foundry_version(&self) -> Option<&str> {
+        self.exec
+            .as_ref()
+            .and_then(|exec| exec.min_foundry_version.as_deref())
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.iter().map(|tag| tag.trim()) {
+            if !tag.is_empty() && !tags.iter().any(|seen| seen == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+
+    pub fn exec_min_gas_limit(&self) -> Option<u64> {
+        self.exec.as_ref().and_then(|exec| exec.min_gas_limit)
+    }
+
+    pub fn exec_rlimits(&self) -> Option<&ExecRlimitsSchema> {
+        self.exec.as_ref().and_then(|exec| exec.rlimits.as_ref())
+    }
+
     pub fn exec_limit(&self) -> u32 {
         self.exec
             .as_ref()
@@ -187,6 +656,127 @@ impl CapabilitiesFile {
     }
 }
 
+/// Prefix of a host call error whose remainder is a [`CapabilityDenial`]
+/// as JSON, so hosts can tell denials apart from other failures.
+pub const CAPABILITY_DENIED_PREFIX: &str = "capability_denied: ";
+
+/// A denied http, exec, or secret access. `allow` is the capabilities
+/// fragment that would have permitted it, for hosts that offer to grant
+/// the capability instead of just failing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapabilityDenial {
+    pub capability: &'static str,
+    pub resource: String,
+    pub allow: Option<Value>,
+    pub message: String,
+}
+
+impl CapabilityDenial {
+    pub fn http(method: &str, url: &str) -> Self {
+        let parsed = Url::parse(url).ok();
+        let host = parsed
+            .as_ref()
+            .and_then(|parsed| parsed.host_str())
+            .map(str::to_string);
+
+        Self {
+            capability: "http",
+            resource: host.clone().unwrap_or_else(|| url.to_string()),
+            allow: host.map(|host| {
+                json!({
+                    "http": {
+                        "allowlist": [{
+                            "host": host,
+                            "path_prefix": parsed.as_ref().map(Url::path),
+                            "methods": [method.to_ascii_uppercase()],
+                        }]
+                    }
+                })
+            }),
+            message: format!("http request blocked by allowlist: {} {}", method, url),
+        }
+    }
+
+    pub fn exec(program: &str, subcommand: &str, message: String) -> Self {
+        let subcommands: Vec<&str> = [subcommand].into_iter().filter(|s| !s.is_empty()).collect();
+
+        Self {
+            capability: "exec",
+            resource: program.to_string(),
+            allow: Some(json!({
+                "exec": {
+                    "allowlist": [{
+                        "program": program,
+                        "allowed_subcommands": subcommands,
+                    }]
+                }
+            })),
+            message,
+        }
+    }
+
+    pub fn exec_target(program: &str, subcommand: &str, target: &str) -> Self {
+        Self {
+            capability: "exec",
+            resource: program.to_string(),
+            allow: Some(json!({
+                "exec": {
+                    "allowlist": [{
+                        "program": program,
+                        "allowed_subcommands": [subcommand],
+                        "allowed_targets": [target],
+                    }]
+                }
+            })),
+            message: format!(
+                "target '{}' not in allowed_targets for program '{}'",
+                target, program
+            ),
+        }
+    }
+
+    pub fn rpc_url(url: &str) -> Self {
+        Self {
+            capability: "network",
+            resource: url.to_string(),
+            allow: Some(json!({ "network": { "rpc_allowlist": [url] } })),
+            message: format!("rpc url '{}' not in network.rpc_allowlist", url),
+        }
+    }
+
+    /// No grant lifts `read_only`; the tool's own marker has to change.
+    pub fn read_only(resource: &str, message: String) -> Self {
+        Self {
+            capability: "read_only",
+            resource: resource.to_string(),
+            allow: None,
+            message,
+        }
+    }
+
+    pub fn secret(name: &str, message: String) -> Self {
+        Self {
+            capability: "secret",
+            resource: name.to_string(),
+            allow: Some(json!({ "secrets": { "allowed_names": [name] } })),
+            message,
+        }
+    }
+
+    pub fn into_error(self) -> String {
+        let payload = serde_json::to_string(&self).unwrap_or_else(|_| {
+            json!({ "capability": self.capability, "message": self.message }).to_string()
+        });
+        format!("{}{}", CAPABILITY_DENIED_PREFIX, payload)
+    }
+}
+
+impl fmt::Display for CapabilityDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HttpCapabilitySchema {
     #[serde(default)]
@@ -201,6 +791,23 @@ pub struct HttpCapabilitySchema {
     pub max_response_bytes: Option<usize>,
     #[serde(default)]
     pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    /// MIME types flagged as text in `body_is_text` on top of the built-in
+    /// text/*, JSON, and XML types. A trailing `*` matches by prefix.
+    #[serde(default)]
+    pub text_content_types: Vec<String>,
+    /// Once reached, only hosts already contacted are allowed.
+    #[serde(default)]
+    pub max_distinct_hosts: Option<usize>,
+    /// Set to a generated UUID on POST, PUT, PATCH, and DELETE requests that
+    /// don't already carry it.
+    #[serde(default)]
+    pub idempotency_header: Option<String>,
+    /// Response headers returned to the tool, matched case-insensitively.
+    /// Unset returns every header except `set-cookie`.
+    #[serde(default)]
+    pub response_header_allowlist: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -210,6 +817,14 @@ pub struct EndpointPatternSchema {
     pub path_prefix: Option<String>,
     #[serde(default)]
     pub methods: Vec<String>,
+    #[serde(default)]
+    pub cost: Option<u32>,
+}
+
+impl EndpointPatternSchema {
+    pub fn request_cost(&self) -> u32 {
+        self.cost.unwrap_or(1)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -218,6 +833,25 @@ pub struct CredentialMappingSchema {
     pub location: CredentialLocationSchema,
     #[serde(default)]
     pub host_patterns: Vec<String>,
+    /// Request paths the credential is injected on (exact, or a prefix
+    /// ending in `*`). Empty means every path.
+    #[serde(default)]
+    pub path_patterns: Vec<String>,
+}
+
+impl CredentialMappingSchema {
+    pub fn applies_to(&self, host: &str, path: &str) -> bool {
+        (self.host_patterns.is_empty()
+            || self
+                .host_patterns
+                .iter()
+                .any(|pattern| host_matches_pattern(host, pattern)))
+            && (self.path_patterns.is_empty()
+                || self
+                    .path_patterns
+                    .iter()
+                    .any(|pattern| match_pattern(pattern, path)))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -238,6 +872,11 @@ pub enum CredentialLocationSchema {
     UrlPath {
         placeholder: String,
     },
+    /// Sets the secret at an RFC 6901 pointer (e.g. `/auth/api_key`) in a
+    /// JSON request body.
+    BodyField {
+        json_pointer: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -266,6 +905,8 @@ pub struct ToolInvokeCapabilitySchema {
 pub struct WorkspaceCapabilitySchema {
     #[serde(default)]
     pub allowed_prefixes: Vec<String>,
+    #[serde(default)]
+    pub max_read_bytes: Option<usize>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -278,6 +919,60 @@ pub struct ExecCapabilitySchema {
     pub rate_limit: Option<RateLimitSchema>,
     #[serde(default)]
     pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub nonce_management: bool,
+    /// Minimum Foundry version (e.g. "1.0.0") required before running
+    /// `cast` or `forge`, checked once per process via `--version`.
+    #[serde(default)]
+    pub min_foundry_version: Option<String>,
+    /// Declares that re-running the same command is safe. Commands from
+    /// tools that don't declare this are never retried.
+    #[serde(default)]
+    pub idempotent: bool,
+    /// When set, commands inherit only `PATH` and these host variables (a
+    /// trailing `*` matches by prefix). Unset inherits the host environment.
+    #[serde(default)]
+    pub env_passthrough: Option<Vec<String>>,
+    /// Floor for the `--gas-limit` of a `cast send` or `cast mktx`: a lower
+    /// or missing value is raised to it before the command runs.
+    #[serde(default)]
+    pub min_gas_limit: Option<u64>,
+    #[serde(default)]
+    pub rlimits: Option<ExecRlimitsSchema>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecRlimitsSchema {
+    #[serde(default)]
+    pub cpu_secs: Option<u64>,
+    #[serde(default)]
+    pub address_space_bytes: Option<u64>,
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    /// Processes for the host user (`RLIMIT_NPROC`); counts every process the
+    /// user owns, not just the command's.
+    #[serde(default)]
+    pub max_processes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkCapabilitySchema {
+    /// RPC URLs `cast`/`forge` may be pointed at, compared before secret
+    /// placeholders are resolved. A trailing `*` matches by prefix.
+    #[serde(default)]
+    pub rpc_allowlist: Vec<String>,
+}
+
+/// Applied to a successful tool output in order: `extract` replaces the
+/// output with the value at a JSON pointer, then `pick` keeps only the
+/// listed top-level keys of an object.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputTransformSchema {
+    #[serde(default)]
+    pub extract: Option<String>,
+    #[serde(default)]
+    pub pick: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -287,12 +982,28 @@ pub struct ExecAllowlistEntry {
     pub allowed_subcommands: Vec<String>,
     #[serde(default)]
     pub blocked_flags: Vec<String>,
+    #[serde(default)]
+    pub cost: Option<u32>,
+    #[serde(default)]
+    pub warning_patterns: Vec<String>,
+    /// Addresses the argument after a [`TARGET_SUBCOMMANDS`] subcommand may
+    /// name, ignoring case. A trailing `*` matches by prefix.
+    #[serde(default)]
+    pub allowed_targets: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecCredentialMapping {
     pub secret_name: String,
     pub injection: ExecCredentialInjection,
+    /// Regex the resolved secret must match in full before the command
+    /// runs, e.g. `(0x)?[0-9a-fA-F]{64}` for a private key.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+fn secret_format_regex(format: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", format))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -344,6 +1055,10 @@ pub struct OAuthConfigSchema {
     pub extra_params: HashMap<String, String>,
     #[serde(default = "default_access_token_field")]
     pub access_token_field: String,
+    /// Secret holding the refresh token exchanged at `token_url`. Defaults
+    /// to `<secret_name>_REFRESH_TOKEN`.
+    #[serde(default)]
+    pub refresh_token_secret: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -379,6 +1094,27 @@ fn default_success_status() -> u16 {
     200
 }
 
+fn sorted<T>(map: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn lint_rate_limit(
+    section: &str,
+    rate_limit: Option<&RateLimitSchema>,
+    warnings: &mut Vec<String>,
+) {
+    if let Some(rate) = rate_limit
+        && rate.requests_per_minute > rate.requests_per_hour
+    {
+        warnings.push(format!(
+            "{}.rate_limit: requests_per_minute ({}) exceeds requests_per_hour ({})",
+            section, rate.requests_per_minute, rate.requests_per_hour
+        ));
+    }
+}
+
 pub fn host_matches_pattern(host: &str, pattern: &str) -> bool {
     if host.eq_ignore_ascii_case(pattern) {
         return true;
@@ -397,7 +1133,16 @@ pub fn host_matches_pattern(host: &str, pattern: &str) -> bool {
     false
 }
 
-fn match_pattern(pattern: &str, value: &str) -> bool {
+/// The first non-flag argument, since global flags may come before the
+/// subcommand (`cast -q send`).
+pub fn exec_subcommand(args: &[String]) -> &str {
+    args.iter()
+        .map(String::as_str)
+        .find(|arg| !arg.starts_with('-'))
+        .unwrap_or("")
+}
+
+pub fn match_pattern(pattern: &str, value: &str) -> bool {
     if pattern == value {
         return true;
     }
@@ -413,7 +1158,96 @@ fn match_pattern(pattern: &str, value: &str) -> bool {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use super::{CapabilitiesFile, host_matches_pattern};
+    use super::{AuthCapabilitySchema, CapabilitiesFile, OAuthConfigSchema, host_matches_pattern};
+
+    #[test]
+    fn read_only_denies_mutating_exec_commands_and_non_get_http() {
+        let caps: CapabilitiesFile = serde_json::from_value(serde_json::json!({
+            "read_only": true,
+            "exec": { "allowlist": [
+                { "program": "cast", "allowed_subcommands": ["call", "send"] },
+                { "program": "forge", "allowed_subcommands": ["create"] },
+                { "program": "jq" }
+            ] }
+        }))
+        .unwrap();
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        let denied = caps
+            .exec_allowed("cast", &args(&["send", "0xabc", "--rpc-url", "http://x"]))
+            .unwrap_err();
+        assert_eq!(denied.capability, "read_only");
+        assert_eq!(denied.allow, None);
+        assert_eq!(
+            denied.message,
+            "tool is read_only; 'cast send' may change state"
+        );
+        assert!(caps.exec_allowed("forge", &args(&["create"])).is_err());
+        assert!(caps.exec_allowed("cast", &args(&["call", "0xabc"])).is_ok());
+        // Programs no mutating command names need no subcommand list.
+        assert!(caps.exec_allowed("jq", &args(&[".result"])).is_ok());
+
+        assert!(
+            caps.read_only_http_allowed("get", "https://api.example.com")
+                .is_ok()
+        );
+        let denied = caps
+            .read_only_http_allowed("post", "https://api.example.com")
+            .unwrap_err();
+        assert_eq!(
+            denied.message,
+            "tool is read_only; http POST requests are not allowed"
+        );
+
+        let custom = CapabilitiesFile {
+            mutating_commands: Some(vec!["cast call".to_string()]),
+            ..caps.clone()
+        };
+        assert!(custom.exec_allowed("cast", &args(&["call"])).is_err());
+        assert!(custom.exec_allowed("cast", &args(&["send"])).is_ok());
+
+        let writable = CapabilitiesFile {
+            read_only: false,
+            ..caps
+        };
+        assert!(writable.exec_allowed("cast", &args(&["send"])).is_ok());
+        assert!(
+            writable
+                .read_only_http_allowed("POST", "https://api.example.com")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn read_only_catches_aliases_leading_flags_and_unpinned_subcommands() {
+        let caps: CapabilitiesFile = serde_json::from_value(serde_json::json!({
+            "read_only": true,
+            "exec": { "allowlist": [{ "program": "cast" }, { "program": "forge" }] }
+        }))
+        .unwrap();
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        for (program, command, expected) in [
+            ("cast", &["s", "0xabc"][..], "'cast s'"),
+            ("cast", &["p", "0x02f8"][..], "'cast p'"),
+            ("cast", &["-q", "send", "0xabc"][..], "'cast send'"),
+            ("forge", &["--quiet", "c", "src/A.sol:A"][..], "'forge c'"),
+        ] {
+            let denied = caps.exec_allowed(program, &args(command)).unwrap_err();
+            assert_eq!(denied.capability, "read_only");
+            assert!(denied.message.contains(expected), "{}", denied.message);
+        }
+
+        let denied = caps
+            .exec_allowed("cast", &args(&["call", "0xabc"]))
+            .unwrap_err();
+        assert_eq!(denied.capability, "read_only");
+        assert!(
+            denied.message.contains("must set allowed_subcommands"),
+            "{}",
+            denied.message
+        );
+    }
 
     #[test]
     fn wildcard_hosts_match() {
@@ -441,6 +1275,7 @@ mod tests {
         let caps = CapabilitiesFile {
             workspace: Some(super::WorkspaceCapabilitySchema {
                 allowed_prefixes: vec!["docs/".to_string()],
+                max_read_bytes: None,
             }),
             ..Default::default()
         };
@@ -459,16 +1294,112 @@ mod tests {
                     host: "api.example.com".to_string(),
                     path_prefix: Some("/v1/".to_string()),
                     methods: vec!["GET".to_string(), "POST".to_string()],
+                    cost: None,
                 }],
                 ..Default::default()
             }),
             ..Default::default()
         };
 
-        assert!(caps.http_allowed("GET", "https://api.example.com/v1/users"));
-        assert!(caps.http_allowed("post", "https://api.example.com/v1/users"));
-        assert!(!caps.http_allowed("DELETE", "https://api.example.com/v1/users"));
-        assert!(!caps.http_allowed("GET", "https://api.example.com/v2/users"));
+        let allowed = |method, url| caps.http_allowed(method, url).is_some();
+
+        assert!(allowed("GET", "https://api.example.com/v1/users"));
+        assert!(allowed("post", "https://api.example.com/v1/users"));
+        assert!(!allowed("DELETE", "https://api.example.com/v1/users"));
+        assert!(!allowed("GET", "https://api.example.com/v2/users"));
+    }
+
+    #[test]
+    fn validate_reports_inconsistent_capabilities() {
+        let raw = serde_json::json!({
+            "http": {
+                "credentials": {
+                    "api": {
+                        "secret_name": "API_KEY",
+                        "location": { "type": "body_field", "json_pointer": "auth/key" }
+                    }
+                },
+                "rate_limit": { "requests_per_minute": 100, "requests_per_hour": 50 }
+            },
+            "exec": {
+                "allowlist": [{ "program": "cast", "cost": 0 }],
+                "credentials": {
+                    "signer": {
+                        "secret_name": "ETH_PRIVATE_KEY",
+                        "injection": { "type": "arg", "flag": "--private-key" },
+                        "format": "(0x"
+                    }
+                }
+            },
+            "secrets": { "allowed_names": ["ETH_*"] },
+            "auth": {
+                "secret_name": "API_KEY",
+                "oauth": { "authorization_url": "https://a.example", "token_url": "https://t.example" },
+                "validation_endpoint": { "url": "not a url" }
+            },
+            "output_transform": { "extract": "result" }
+        })
+        .to_string();
+
+        let result = CapabilitiesFile::validate_json(&raw);
+        assert_eq!(result.valid, false);
+        assert_eq!(
+            result.errors,
+            vec![
+                "http.credentials.api: secret 'API_KEY' is not allowed by secrets.allowed_names",
+                "http.credentials.api: json_pointer 'auth/key' must start with '/'",
+                "exec.credentials.signer: format '(0x' is not a valid regex",
+                "auth.validation_endpoint.url 'not a url' is not a valid URL: relative URL without a base",
+                "output_transform.extract 'result' must start with '/'",
+            ]
+        );
+        assert_eq!(
+            result.warnings,
+            vec![
+                "http.credentials is set but http.allowlist is empty, so no request can use them",
+                "http.rate_limit: requests_per_minute (100) exceeds requests_per_hour (50)",
+                "exec.allowlist[0]: cost 0 makes 'cast' free of rate limits",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_json_reports_parse_errors_and_accepts_clean_files() {
+        let result = CapabilitiesFile::validate_json(r#"{"exec": {"allowlist": [{}]}}"#);
+        assert_eq!(result.valid, false);
+        assert!(
+            result.errors[0].starts_with("failed to parse capabilities: missing field `program`"),
+            "{:?}",
+            result.errors
+        );
+
+        let clean = r#"{
+            "exec": { "allowlist": [{ "program": "cast", "allowed_subcommands": ["call"] }] },
+            "secrets": { "allowed_names": ["ETH_*"] }
+        }"#;
+        assert_eq!(
+            CapabilitiesFile::validate_json(clean),
+            super::CapabilitiesValidation {
+                valid: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_deduplicated() {
+        let raw = r#"{ "tags": ["ethereum", " destructive ", "", "ethereum"] }"#;
+        let caps: CapabilitiesFile = serde_json::from_str(raw).unwrap();
+        assert_eq!(caps.tags(), vec!["ethereum", "destructive"]);
+
+        let result = CapabilitiesFile::validate_json(raw);
+        assert_eq!(result.valid, true);
+        assert_eq!(
+            result.warnings,
+            vec!["tags contains an empty tag, which is ignored"]
+        );
+
+        assert!(CapabilitiesFile::default().tags().is_empty());
     }
 
     #[test]
@@ -480,6 +1411,15 @@ mod tests {
             secrets: Some(Default::default()),
             auth: Some(Default::default()),
             exec: None,
+            max_host_calls: None,
+            capture_stdio: false,
+            max_additional_fuel: None,
+            output_transform: None,
+            network: None,
+            tags: Vec::new(),
+            default_params: Default::default(),
+            read_only: false,
+            mutating_commands: None,
         };
 
         let summary = caps.summary();
@@ -489,6 +1429,7 @@ mod tests {
         assert_eq!(summary.secrets, true);
         assert_eq!(summary.auth, true);
         assert_eq!(summary.exec, false);
+        assert_eq!(summary.exec_idempotent, false);
     }
 
     #[test]
@@ -499,6 +1440,9 @@ mod tests {
                     program: "cast".to_string(),
                     allowed_subcommands: vec!["send".to_string(), "call".to_string()],
                     blocked_flags: vec!["--interactive".to_string()],
+                    cost: None,
+                    warning_patterns: Vec::new(),
+                    allowed_targets: Vec::new(),
                 }],
                 ..Default::default()
             }),
@@ -525,6 +1469,215 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn exec_denials_suggest_the_missing_allowlist_entry() {
+        let caps = CapabilitiesFile {
+            exec: Some(super::ExecCapabilitySchema {
+                allowlist: vec![super::ExecAllowlistEntry {
+                    program: "cast".to_string(),
+                    allowed_subcommands: vec!["call".to_string()],
+                    blocked_flags: vec!["--interactive".to_string()],
+                    cost: None,
+                    warning_patterns: Vec::new(),
+                    allowed_targets: Vec::new(),
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let denial = caps
+            .exec_allowed("cast", &["send".to_string()])
+            .unwrap_err();
+        assert_eq!(denial.capability, "exec");
+        assert_eq!(denial.resource, "cast");
+        assert_eq!(
+            denial.allow,
+            Some(serde_json::json!({ "exec": { "allowlist": [{
+                "program": "cast",
+                "allowed_subcommands": ["send"]
+            }] } }))
+        );
+
+        let denial = caps
+            .exec_allowed("cast", &["call".to_string(), "--interactive".to_string()])
+            .unwrap_err();
+        assert_eq!(denial.allow, None);
+        assert_eq!(
+            denial.to_string(),
+            "blocked flag '--interactive' for program 'cast'"
+        );
+
+        let error = CapabilitiesFile::default()
+            .exec_allowed("forge", &[])
+            .unwrap_err()
+            .into_error();
+        let payload: serde_json::Value =
+            serde_json::from_str(error.strip_prefix(super::CAPABILITY_DENIED_PREFIX).unwrap())
+                .unwrap();
+        assert_eq!(payload["message"], "exec capability not granted");
+        assert_eq!(
+            payload["allow"]["exec"]["allowlist"][0]["allowed_subcommands"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn rpc_allowlist_restricts_rpc_urls() {
+        let caps: CapabilitiesFile = serde_json::from_str(
+            r#"{
+                "network": { "rpc_allowlist": [
+                    "http://127.0.0.1:8545",
+                    "https://eth-mainnet.g.alchemy.com/v2/*"
+                ] }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(caps.rpc_url_allowed("http://127.0.0.1:8545").is_ok());
+        assert!(
+            caps.rpc_url_allowed("https://eth-mainnet.g.alchemy.com/v2/{{SECRET:RPC_KEY}}")
+                .is_ok()
+        );
+
+        let denial = caps
+            .rpc_url_allowed("https://rpc.evil.example")
+            .unwrap_err();
+        assert_eq!(denial.capability, "network");
+        assert_eq!(
+            denial.message,
+            "rpc url 'https://rpc.evil.example' not in network.rpc_allowlist"
+        );
+        assert_eq!(
+            denial.allow,
+            Some(serde_json::json!({
+                "network": { "rpc_allowlist": ["https://rpc.evil.example"] }
+            }))
+        );
+
+        assert!(
+            CapabilitiesFile::default()
+                .rpc_url_allowed("https://rpc.evil.example")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn exec_allowed_targets_restrict_the_call_target() {
+        let caps: CapabilitiesFile = serde_json::from_str(
+            r#"{
+                "exec": { "allowlist": [{
+                    "program": "cast",
+                    "allowed_subcommands": ["call", "send"],
+                    "allowed_targets": [
+                        "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                        "0xdead*"
+                    ]
+                }] }
+            }"#,
+        )
+        .unwrap();
+        let args = |subcommand: &str, target: &str| {
+            vec![
+                subcommand.to_string(),
+                target.to_string(),
+                "balanceOf(address)".to_string(),
+            ]
+        };
+
+        assert!(
+            caps.exec_allowed(
+                "cast",
+                &args("call", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+            )
+            .is_ok()
+        );
+        assert!(
+            caps.exec_allowed(
+                "cast",
+                &args("send", "0xDEAD000000000000000000000000000000000001")
+            )
+            .is_ok()
+        );
+
+        let denial = caps
+            .exec_allowed(
+                "cast",
+                &args("call", "0x1111111111111111111111111111111111111111"),
+            )
+            .unwrap_err();
+        assert_eq!(
+            denial.to_string(),
+            "target '0x1111111111111111111111111111111111111111' not in allowed_targets for program 'cast'"
+        );
+        assert_eq!(
+            denial.allow.unwrap()["exec"]["allowlist"][0]["allowed_targets"],
+            serde_json::json!(["0x1111111111111111111111111111111111111111"])
+        );
+
+        // A flag in the target position never matches an address.
+        assert!(
+            caps.exec_allowed("cast", &["call".to_string(), "--rpc-url".to_string()])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn exec_allowed_targets_leave_commands_without_a_target_alone() {
+        let caps: CapabilitiesFile = serde_json::from_str(
+            r#"{
+                "exec": { "allowlist": [{
+                    "program": "cast",
+                    "allowed_targets": ["0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"]
+                }] }
+            }"#,
+        )
+        .unwrap();
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let raw_tx = "0x02f86b0180843b9aca00";
+
+        // cast_send's preflight and predict_hash helpers.
+        for allowed in [
+            args(&["chain-id", "--rpc-url", "https://rpc.example.com"]),
+            args(&["client", "--rpc-url", "https://rpc.example.com"]),
+            args(&["keccak", raw_tx]),
+            args(&["publish", raw_tx, "--rpc-url", "https://rpc.example.com"]),
+            args(&[
+                "mktx",
+                "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "approve()",
+            ]),
+        ] {
+            assert!(caps.exec_allowed("cast", &allowed).is_ok(), "{allowed:?}");
+        }
+
+        // Aliases and the signing step still name a target.
+        for denied in [
+            args(&["s", "0x1111111111111111111111111111111111111111"]),
+            args(&["mktx", "0x1111111111111111111111111111111111111111"]),
+        ] {
+            assert!(caps.exec_allowed("cast", &denied).is_err(), "{denied:?}");
+        }
+    }
+
+    #[test]
+    fn allowlist_entries_default_to_unit_cost() {
+        let parsed: CapabilitiesFile = serde_json::from_str(
+            r#"{
+                "http": { "allowlist": [{ "host": "api.example.com", "cost": 3 }] },
+                "exec": { "allowlist": [{ "program": "forge", "cost": 5 }, { "program": "cast" }] }
+            }"#,
+        )
+        .unwrap();
+
+        let pattern = parsed
+            .http_allowed("GET", "https://api.example.com/")
+            .unwrap();
+        assert_eq!(pattern.request_cost(), 3);
+        assert_eq!(parsed.exec_cost("forge"), 5);
+        assert_eq!(parsed.exec_cost("cast"), 1);
+    }
+
     #[test]
     fn parses_exec_capability_schema() {
         let parsed: CapabilitiesFile = serde_json::from_str(
@@ -592,4 +1745,32 @@ mod tests {
             "access_token"
         );
     }
+
+    #[test]
+    fn oauth_refresh_secret_defaults_from_secret_name() {
+        let mut caps = CapabilitiesFile {
+            auth: Some(AuthCapabilitySchema {
+                secret_name: "GITHUB_TOKEN".to_string(),
+                oauth: Some(OAuthConfigSchema::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (_, _, refresh_secret) = caps.oauth_refresh().expect("oauth configured");
+        assert_eq!(refresh_secret, "GITHUB_TOKEN_REFRESH_TOKEN");
+
+        caps.auth
+            .as_mut()
+            .unwrap()
+            .oauth
+            .as_mut()
+            .unwrap()
+            .refresh_token_secret = Some("GITHUB_REFRESH".to_string());
+        let (_, _, refresh_secret) = caps.oauth_refresh().expect("oauth configured");
+        assert_eq!(refresh_secret, "GITHUB_REFRESH");
+
+        caps.auth.as_mut().unwrap().oauth = None;
+        assert!(caps.oauth_refresh().is_none());
+    }
 }
@@ -1,23 +1,71 @@
 mod capabilities;
+mod oauth;
 mod protocol;
 mod runtime;
+mod stdio;
+#[cfg(test)]
+mod test_http;
 
 use std::collections::{HashMap, VecDeque};
 use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
-use serde_json::json;
+use serde_json::{Value, json};
 
-use protocol::{OutboundMessage, Request};
+use capabilities::CapabilitiesFile;
+use protocol::{DiscoverResult, OutboundMessage, Request, RuntimeLog};
 use runtime::{Runtime, RuntimeDefaults};
 
 const PROTOCOL_VERSION: u32 = 1;
+const MIN_PROTOCOL_VERSION: u32 = 1;
+/// Sent by a host in `Hello.version` to ask for the supported range instead
+/// of asserting a specific version.
+const NEGOTIATE_PROTOCOL_VERSION: u32 = 0;
 const HOST_CALL_TIMEOUT: Duration = Duration::from_secs(60);
+/// Set to `json` to write the sidecar's own diagnostics to stderr as
+/// `RuntimeLog`-shaped JSON lines instead of plain text.
+const LOG_FORMAT_ENV: &str = "LEMON_WASM_LOG_FORMAT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiagnosticFormat {
+    Text,
+    Json,
+}
+
+impl DiagnosticFormat {
+    fn from_env() -> Self {
+        match std::env::var(LOG_FORMAT_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+
+    fn render(self, level: &str, message: &str) -> String {
+        match self {
+            Self::Text => message.to_string(),
+            Self::Json => {
+                let log = RuntimeLog {
+                    level: level.to_string(),
+                    message: message.to_string(),
+                    timestamp_millis: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_millis() as u64)
+                        .unwrap_or(0),
+                };
+                serde_json::to_string(&log).unwrap_or_else(|_| message.to_string())
+            }
+        }
+    }
+
+    fn emit(self, level: &str, message: &str) {
+        eprintln!("{}", self.render(level, message));
+    }
+}
 
 #[derive(Debug, Clone)]
 struct HostCallResultPayload {
@@ -50,11 +98,10 @@ impl RequestQueue {
         self.rx.recv().ok()
     }
 
-    fn recv_next_timeout(&mut self, timeout: Duration) -> Option<Request> {
-        if let Some(req) = self.deferred.pop_front() {
-            return Some(req);
-        }
-
+    /// Reads only new requests. Deferred ones are left for the main loop:
+    /// re-popping them here would spin on them and never reach a host call
+    /// result queued behind them.
+    fn recv_new_timeout(&mut self, timeout: Duration) -> Option<Request> {
         self.rx.recv_timeout(timeout).ok()
     }
 
@@ -71,14 +118,109 @@ impl RequestQueue {
     }
 }
 
+#[derive(Debug)]
+struct EventBudget {
+    limit: Option<u32>,
+    attempted: AtomicU32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventAdmission {
+    Emit,
+    /// The first event past the limit: send the truncation notice instead.
+    Truncate,
+    Drop,
+}
+
+impl EventBudget {
+    fn new(limit: Option<u32>) -> Self {
+        Self {
+            limit,
+            attempted: AtomicU32::new(0),
+        }
+    }
+
+    fn admit(&self) -> EventAdmission {
+        let Some(limit) = self.limit else {
+            return EventAdmission::Emit;
+        };
+
+        match self.attempted.fetch_add(1, Ordering::Relaxed) {
+            count if count < limit => EventAdmission::Emit,
+            count if count == limit => EventAdmission::Truncate,
+            _ => EventAdmission::Drop,
+        }
+    }
+}
+
+/// Counters reported by `Status`. Shared with host-call waits so a probe
+/// is still answered while an invocation is blocked on the host.
+#[derive(Debug)]
+struct SidecarStats {
+    started: Instant,
+    tool_count: AtomicUsize,
+    total_invocations: AtomicU64,
+    active_invocations: AtomicUsize,
+}
+
+/// Counts an invocation as active until dropped.
+struct ActiveInvocation {
+    stats: Arc<SidecarStats>,
+}
+
+impl SidecarStats {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            tool_count: AtomicUsize::new(0),
+            total_invocations: AtomicU64::new(0),
+            active_invocations: AtomicUsize::new(0),
+        }
+    }
+
+    fn record_discover(&self, result: &DiscoverResult) {
+        self.tool_count.store(result.tools.len(), Ordering::Relaxed);
+    }
+
+    fn record_invoke(self: &Arc<Self>) -> ActiveInvocation {
+        self.total_invocations.fetch_add(1, Ordering::Relaxed);
+        self.active_invocations.fetch_add(1, Ordering::Relaxed);
+        ActiveInvocation {
+            stats: self.clone(),
+        }
+    }
+
+    fn status(&self) -> Value {
+        json!({
+            "uptime_ms": self.started.elapsed().as_millis() as u64,
+            "tool_count": self.tool_count.load(Ordering::Relaxed),
+            "total_invocations": self.total_invocations.load(Ordering::Relaxed),
+            "active_invocations": self.active_invocations.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl Drop for ActiveInvocation {
+    fn drop(&mut self) {
+        self.stats
+            .active_invocations
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 fn main() {
-    if let Err(err) = run() {
-        eprintln!("fatal sidecar error: {err:#}");
+    let diagnostics = DiagnosticFormat::from_env();
+    if let Err(err) = run(diagnostics) {
+        diagnostics.emit("error", &format!("fatal sidecar error: {err:#}"));
         std::process::exit(1);
     }
 }
 
-fn run() -> Result<()> {
+fn parse_request_line(line: &str) -> Result<Request, String> {
+    serde_json::from_str::<Request>(line).map_err(|err| format!("invalid sidecar request: {err}"))
+}
+
+fn run(diagnostics: DiagnosticFormat) -> Result<()> {
     let (tx, rx) = mpsc::channel::<Request>();
 
     std::thread::spawn(move || {
@@ -97,19 +239,17 @@ fn run() -> Result<()> {
                         continue;
                     }
 
-                    match serde_json::from_str::<Request>(trimmed) {
+                    match parse_request_line(trimmed) {
                         Ok(req) => {
                             if tx.send(req).is_err() {
                                 break;
                             }
                         }
-                        Err(err) => {
-                            eprintln!("invalid sidecar request: {err}");
-                        }
+                        Err(message) => diagnostics.emit("warn", &message),
                     }
                 }
                 Err(err) => {
-                    eprintln!("stdin read error: {err}");
+                    diagnostics.emit("error", &format!("stdin read error: {err}"));
                     break;
                 }
             }
@@ -118,6 +258,7 @@ fn run() -> Result<()> {
 
     let queue = Arc::new(Mutex::new(RequestQueue::new(rx)));
     let call_seq = Arc::new(AtomicU64::new(0));
+    let stats = Arc::new(SidecarStats::new());
 
     let mut runtime = Runtime::new(RuntimeDefaults::default())?;
 
@@ -132,34 +273,36 @@ fn run() -> Result<()> {
         };
 
         match request {
-            Request::Hello { id, version } => {
-                if let Some(version) = version {
-                    if version != PROTOCOL_VERSION {
-                        emit_message(&OutboundMessage::response_err(
-                            id,
-                            format!(
-                                "unsupported protocol version {version}; expected {PROTOCOL_VERSION}"
-                            ),
-                        ))?;
-                        continue;
-                    }
-                }
+            Request::Hello {
+                id,
+                version,
+                strict,
+            } => match hello_result(version, strict) {
+                Ok(result) => emit_message(&OutboundMessage::response_ok(id, result))?,
+                Err(err) => emit_message(&OutboundMessage::response_err(id, err))?,
+            },
+            Request::Discover {
+                id,
+                paths,
+                compile_only: true,
+                ..
+            } => {
+                let result = runtime.compile_check(paths.into_iter().map(PathBuf::from).collect());
 
                 emit_message(&OutboundMessage::response_ok(
                     id,
-                    json!({
-                        "version": PROTOCOL_VERSION,
-                        "name": "lemon-wasm-runtime"
-                    }),
+                    serde_json::to_value(result).context("failed to encode compile check")?,
                 ))?;
             }
             Request::Discover {
                 id,
                 paths,
                 defaults,
+                ..
             } => {
                 let discover_paths = paths.into_iter().map(PathBuf::from).collect();
                 let result = runtime.discover(discover_paths, RuntimeDefaults::from(defaults));
+                stats.record_discover(&result);
 
                 emit_message(&OutboundMessage::response_ok(
                     id,
@@ -171,28 +314,46 @@ fn run() -> Result<()> {
                 tool,
                 params_json,
                 context_json,
+                max_depth,
             } => {
+                let _active = stats.record_invoke();
                 let queue_for_host = queue.clone();
+                let stats_for_host = stats.clone();
                 let call_seq_for_host = call_seq.clone();
                 let request_id_for_host = id.clone();
+                let events = EventBudget::new(runtime.defaults().max_events_per_invoke);
+
+                let host_invoke = Arc::new(
+                    move |target: String, params: String, idempotency_key: Option<String>| {
+                        let admission = events.admit();
+                        if admission != EventAdmission::Emit {
+                            let limit = events.limit.unwrap_or_default();
+                            if admission == EventAdmission::Truncate {
+                                emit_message(&events_truncated_notice(&request_id_for_host, limit))
+                                    .map_err(|err| format!("failed to emit notice: {err}"))?;
+                            }
+                            return Err(format!(
+                                "event limit reached: max_events_per_invoke is {limit}"
+                            ));
+                        }
 
-                let host_invoke = Arc::new(move |target: String, params: String| {
-                    let seq = call_seq_for_host.fetch_add(1, Ordering::Relaxed) + 1;
-                    let call_id = format!("host_call_{seq}");
+                        let seq = call_seq_for_host.fetch_add(1, Ordering::Relaxed) + 1;
+                        let call_id = format!("host_call_{seq}");
 
-                    emit_message(&OutboundMessage::Event {
-                        event: "host_call".to_string(),
-                        request_id: request_id_for_host.clone(),
-                        call_id: call_id.clone(),
-                        tool: target,
-                        params_json: params,
-                    })
-                    .map_err(|err| format!("failed to emit host_call event: {err}"))?;
+                        emit_message(&host_call_event(
+                            &request_id_for_host,
+                            &call_id,
+                            target,
+                            params,
+                            idempotency_key,
+                        ))
+                        .map_err(|err| format!("failed to emit host_call event: {err}"))?;
 
-                    wait_for_host_call_result(&queue_for_host, &call_id)
-                });
+                        wait_for_host_call_result(&queue_for_host, &stats_for_host, &call_id)
+                    },
+                );
 
-                match runtime.invoke(&tool, &params_json, context_json, host_invoke) {
+                match runtime.invoke(&tool, &params_json, context_json, max_depth, host_invoke) {
                     Ok(result) => emit_message(&OutboundMessage::response_ok(
                         id,
                         serde_json::to_value(result).context("failed to encode invoke response")?,
@@ -220,7 +381,23 @@ fn run() -> Result<()> {
 
                 emit_message(&OutboundMessage::response_ok(id, json!({"accepted": true})))?;
             }
+            Request::ValidateCapabilities {
+                id,
+                capabilities_json,
+            } => {
+                let result = CapabilitiesFile::validate_json(&capabilities_json);
+
+                emit_message(&OutboundMessage::response_ok(
+                    id,
+                    serde_json::to_value(result).context("failed to encode validation response")?,
+                ))?;
+            }
+            Request::Status { id } => {
+                emit_message(&OutboundMessage::response_ok(id, stats.status()))?;
+            }
             Request::Shutdown { id } => {
+                // Invocations run on this loop and a shutdown read during a
+                // host call wait is deferred, so nothing is left in flight.
                 emit_message(&OutboundMessage::response_ok(id, json!({"stopped": true})))?;
                 break;
             }
@@ -230,8 +407,66 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+fn hello_result(version: Option<u32>, strict: bool) -> std::result::Result<Value, String> {
+    let mut result = json!({
+        "version": PROTOCOL_VERSION,
+        "name": "lemon-wasm-runtime"
+    });
+
+    match version {
+        Some(NEGOTIATE_PROTOCOL_VERSION) => {
+            result["supported_versions"] = json!({
+                "min": MIN_PROTOCOL_VERSION,
+                "max": PROTOCOL_VERSION
+            });
+        }
+        Some(version) if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) => {
+            return Err(format!(
+                "unsupported protocol version {version}; expected {PROTOCOL_VERSION}"
+            ));
+        }
+        Some(_) => {}
+        None if strict => {
+            result["warning"] = json!(format!(
+                "no protocol version sent; assuming {PROTOCOL_VERSION}"
+            ));
+        }
+        None => {}
+    }
+
+    Ok(result)
+}
+
+fn host_call_event(
+    request_id: &str,
+    call_id: &str,
+    tool: String,
+    params_json: String,
+    idempotency_key: Option<String>,
+) -> OutboundMessage {
+    OutboundMessage::Event {
+        event: "host_call".to_string(),
+        request_id: request_id.to_string(),
+        call_id: call_id.to_string(),
+        tool,
+        params_json,
+        idempotency_key,
+    }
+}
+
+fn events_truncated_notice(request_id: &str, limit: u32) -> OutboundMessage {
+    OutboundMessage::Notice {
+        event: "events_truncated".to_string(),
+        request_id: request_id.to_string(),
+        message: format!(
+            "invocation reached max_events_per_invoke ({limit}); further events are dropped"
+        ),
+    }
+}
+
 fn wait_for_host_call_result(
     queue: &Arc<Mutex<RequestQueue>>,
+    stats: &SidecarStats,
     target_call_id: &str,
 ) -> std::result::Result<String, String> {
     let deadline = Instant::now() + HOST_CALL_TIMEOUT;
@@ -254,7 +489,7 @@ fn wait_for_host_call_result(
             }
 
             let remaining = deadline.saturating_duration_since(now);
-            guard.recv_next_timeout(remaining)
+            guard.recv_new_timeout(remaining)
         };
 
         let Some(req) = maybe_req else {
@@ -290,6 +525,10 @@ fn wait_for_host_call_result(
                     .map_err(|_| "request queue lock poisoned".to_string())?;
                 guard.store_host_result(call_id, payload);
             }
+            Request::Status { id } => {
+                emit_message(&OutboundMessage::response_ok(id, stats.status()))
+                    .map_err(|err| format!("failed to emit status: {err}"))?;
+            }
             other => {
                 let mut guard = queue
                     .lock()
@@ -323,3 +562,189 @@ fn emit_message(message: &OutboundMessage) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{
+        DiagnosticFormat, EventAdmission, EventBudget, PROTOCOL_VERSION, RequestQueue,
+        SidecarStats, events_truncated_notice, hello_result, host_call_event, parse_request_line,
+        wait_for_host_call_result,
+    };
+    use crate::protocol::{DiscoverResult, Request, RuntimeLog};
+
+    #[test]
+    fn json_diagnostics_are_parseable_runtime_logs() {
+        let message = parse_request_line(r#"{"type":"invoke","id":"#).unwrap_err();
+        assert!(message.starts_with("invalid sidecar request: "));
+
+        let line = DiagnosticFormat::Json.render("warn", &message);
+        assert!(!line.contains('\n'));
+        let log: RuntimeLog = serde_json::from_str(&line).expect("JSON diagnostic line");
+        assert_eq!(log.level, "warn");
+        assert_eq!(log.message, message);
+        assert!(log.timestamp_millis > 0);
+
+        assert_eq!(DiagnosticFormat::Text.render("warn", &message), message);
+    }
+
+    #[test]
+    fn event_budget_caps_an_event_heavy_invoke() {
+        let budget = EventBudget::new(Some(3));
+        let admissions: Vec<EventAdmission> = (0..100).map(|_| budget.admit()).collect();
+
+        assert_eq!(
+            admissions[..4],
+            [
+                EventAdmission::Emit,
+                EventAdmission::Emit,
+                EventAdmission::Emit,
+                EventAdmission::Truncate
+            ]
+        );
+        assert!(admissions[4..].iter().all(|a| *a == EventAdmission::Drop));
+
+        let unlimited = EventBudget::new(None);
+        assert!((0..100).all(|_| unlimited.admit() == EventAdmission::Emit));
+
+        let notice = serde_json::to_value(events_truncated_notice("req_1", 3)).unwrap();
+        assert_eq!(notice["type"], "event");
+        assert_eq!(notice["event"], "events_truncated");
+        assert_eq!(notice["request_id"], "req_1");
+    }
+
+    #[test]
+    fn shutdown_during_a_host_call_is_deferred_until_the_invocation_finishes() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let queue = Arc::new(std::sync::Mutex::new(RequestQueue::new(rx)));
+        let stats = SidecarStats::new();
+
+        tx.send(Request::Shutdown {
+            id: "req_2".to_string(),
+        })
+        .unwrap();
+        tx.send(Request::HostCallResult {
+            id: "req_3".to_string(),
+            call_id: "host_call_1".to_string(),
+            ok: true,
+            output_json: Some("42".to_string()),
+            error: None,
+        })
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        assert_eq!(
+            wait_for_host_call_result(&queue, &stats, "host_call_1"),
+            Ok("42".to_string())
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let next = queue.lock().unwrap().recv_next();
+        assert!(matches!(next, Some(Request::Shutdown { id }) if id == "req_2"));
+    }
+
+    #[test]
+    fn status_reflects_discovered_tools_and_invocations() {
+        let stats = Arc::new(SidecarStats::new());
+
+        let status = stats.status();
+        assert_eq!(status["tool_count"], 0);
+        assert_eq!(status["total_invocations"], 0);
+        assert_eq!(status["active_invocations"], 0);
+        assert!(status["uptime_ms"].is_u64());
+
+        let tool = |name: &str| {
+            serde_json::json!({
+                "name": name,
+                "path": format!("/tools/{name}.wasm"),
+                "description": "",
+                "schema_json": "{}",
+                "capabilities": {
+                    "workspace_read": false, "http": false, "tool_invoke": false,
+                    "secrets": false, "auth": false, "exec": false
+                },
+                "warnings": []
+            })
+        };
+        let discovered: DiscoverResult = serde_json::from_value(serde_json::json!({
+            "tools": [tool("alpha"), tool("beta")],
+            "warnings": [],
+            "errors": []
+        }))
+        .unwrap();
+        stats.record_discover(&discovered);
+
+        drop(stats.record_invoke());
+        let _running = stats.record_invoke();
+
+        let status = stats.status();
+        assert_eq!(status["tool_count"], 2);
+        assert_eq!(status["total_invocations"], 2);
+        assert_eq!(status["active_invocations"], 1);
+    }
+
+    #[test]
+    fn hello_without_version_warns_in_strict_mode() {
+        let result = hello_result(None, true).expect("hello should succeed");
+        assert_eq!(result["version"], PROTOCOL_VERSION);
+        assert_eq!(
+            result["warning"],
+            format!("no protocol version sent; assuming {PROTOCOL_VERSION}")
+        );
+    }
+
+    #[test]
+    fn hello_without_version_is_silent_when_not_strict() {
+        let result = hello_result(None, false).expect("hello should succeed");
+        assert!(result.get("warning").is_none());
+    }
+
+    #[test]
+    fn hello_with_matching_version_succeeds() {
+        let result = hello_result(Some(PROTOCOL_VERSION), true).expect("hello should succeed");
+        assert_eq!(result["version"], PROTOCOL_VERSION);
+        assert!(result.get("warning").is_none());
+        assert!(result.get("supported_versions").is_none());
+    }
+
+    #[test]
+    fn hello_with_mismatched_version_errors() {
+        let err = hello_result(Some(PROTOCOL_VERSION + 1), true).expect_err("should reject");
+        assert!(err.contains("unsupported protocol version"));
+    }
+
+    #[test]
+    fn host_call_event_carries_idempotency_key() {
+        let event = host_call_event(
+            "req_1",
+            "host_call_1",
+            "send_tx".to_string(),
+            "{}".to_string(),
+            Some("send-42".to_string()),
+        );
+        let encoded = serde_json::to_value(&event).expect("encode event");
+        assert_eq!(encoded["event"], "host_call");
+        assert_eq!(encoded["idempotency_key"], "send-42");
+
+        let event = host_call_event(
+            "req_1",
+            "host_call_2",
+            "send_tx".to_string(),
+            "{}".to_string(),
+            None,
+        );
+        let encoded = serde_json::to_value(&event).expect("encode event");
+        assert!(encoded.get("idempotency_key").is_none());
+    }
+
+    #[test]
+    fn hello_with_zero_version_negotiates() {
+        let result = hello_result(Some(0), true).expect("negotiation should succeed");
+        assert_eq!(result["supported_versions"]["min"], 1);
+        assert_eq!(result["supported_versions"]["max"], PROTOCOL_VERSION);
+    }
+}
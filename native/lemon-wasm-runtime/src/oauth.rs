@@ -0,0 +1,304 @@
+//! OAuth refresh-token exchange for tools that declare `auth.oauth`.
+//!
+//! The host trades a stored refresh token for an access token at the
+//! provider's `token_url` and caches it until shortly before it expires.
+//! The access token is injected through the tool's regular
+//! `http.credentials` mapping for `auth.secret_name`; WASM never sees
+//! either token.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use serde_json::Value;
+
+use crate::capabilities::OAuthConfigSchema;
+
+/// Refresh this long before the provider-reported expiry so a token can't
+/// lapse mid-request.
+const EXPIRY_SKEW: Duration = Duration::from_secs(30);
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    /// Set when the provider rotated the refresh token on the last exchange.
+    refresh_token: Option<String>,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| now + EXPIRY_SKEW < expires_at)
+    }
+}
+
+/// Access tokens per (token endpoint, refresh token secret), shared across
+/// invocations so every call doesn't hit the token endpoint.
+#[derive(Debug, Default)]
+pub struct OAuthTokenCache {
+    tokens: Mutex<HashMap<String, CachedToken>>,
+}
+
+impl OAuthTokenCache {
+    /// `Ok(None)` when there is no refresh token to exchange.
+    pub fn access_token(
+        &self,
+        key: &str,
+        oauth: &OAuthConfigSchema,
+        stored_refresh_token: impl FnOnce() -> Option<String>,
+    ) -> Result<Option<String>, String> {
+        // Held across the exchange so concurrent callers don't each refresh.
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|_| "oauth token cache lock poisoned".to_string())?;
+
+        let rotated = match tokens.get(key) {
+            Some(token) if token.is_fresh(Instant::now()) => {
+                return Ok(Some(token.access_token.clone()));
+            }
+            Some(token) => token.refresh_token.clone(),
+            None => None,
+        };
+
+        let Some(refresh_token) = rotated.or_else(stored_refresh_token) else {
+            return Ok(None);
+        };
+
+        let token = exchange_refresh_token(oauth, &refresh_token)?;
+        let access_token = token.access_token.clone();
+        tokens.insert(key.to_string(), token);
+        Ok(Some(access_token))
+    }
+}
+
+fn exchange_refresh_token(
+    oauth: &OAuthConfigSchema,
+    refresh_token: &str,
+) -> Result<CachedToken, String> {
+    let mut form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.to_string()),
+    ];
+
+    if let Some(client_id) = config_value(&oauth.client_id, &oauth.client_id_env) {
+        form.push(("client_id", client_id));
+    }
+    if let Some(client_secret) = config_value(&oauth.client_secret, &oauth.client_secret_env) {
+        form.push(("client_secret", client_secret));
+    }
+    if !oauth.scopes.is_empty() {
+        form.push(("scope", oauth.scopes.join(" ")));
+    }
+
+    let client = Client::builder()
+        .timeout(TOKEN_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|err| format!("failed to build http client: {}", err))?;
+
+    let response = client
+        .post(&oauth.token_url)
+        .header("accept", "application/json")
+        .form(&form)
+        .send()
+        .map_err(|err| format!("oauth token refresh failed: {}", err))?;
+
+    let status = response.status();
+    let body: Value = response.json().unwrap_or(Value::Null);
+
+    if !status.is_success() {
+        return Err(format!(
+            "oauth token refresh failed: token endpoint returned {}{}",
+            status.as_u16(),
+            token_error_detail(&body)
+        ));
+    }
+
+    parse_token_response(&body, &oauth.access_token_field)
+}
+
+/// The provider's `error` / `error_description`, never the raw body, which
+/// may echo credentials back.
+fn token_error_detail(body: &Value) -> String {
+    let error = body.get("error").and_then(Value::as_str);
+    let description = body.get("error_description").and_then(Value::as_str);
+
+    match (error, description) {
+        (Some(error), Some(description)) => format!(": {} ({})", error, description),
+        (Some(error), None) => format!(": {}", error),
+        _ => String::new(),
+    }
+}
+
+/// `access_token_field` may be a dotted path for providers that nest the
+/// token (e.g. `authed_user.access_token`).
+fn parse_token_response(body: &Value, access_token_field: &str) -> Result<CachedToken, String> {
+    let access_token = access_token_field
+        .split('.')
+        .try_fold(body, |value, key| value.get(key))
+        .and_then(Value::as_str)
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| format!("oauth token response has no '{}' field", access_token_field))?;
+
+    let expires_in = match body.get("expires_in") {
+        Some(Value::Number(seconds)) => seconds.as_u64(),
+        Some(Value::String(seconds)) => seconds.parse().ok(),
+        _ => None,
+    };
+
+    Ok(CachedToken {
+        access_token: access_token.to_string(),
+        refresh_token: body
+            .get("refresh_token")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        expires_at: expires_in.map(|seconds| Instant::now() + Duration::from_secs(seconds)),
+    })
+}
+
+fn config_value(value: &Option<String>, env: &Option<String>) -> Option<String> {
+    value
+        .clone()
+        .or_else(|| env.as_ref().and_then(|name| std::env::var(name).ok()))
+        .filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::{OAuthTokenCache, parse_token_response};
+    use crate::capabilities::OAuthConfigSchema;
+    use crate::test_http::{self, ReceivedRequest, Response};
+
+    fn spawn_token_server(
+        responses: Vec<(u16, String)>,
+    ) -> (String, std::thread::JoinHandle<Vec<ReceivedRequest>>) {
+        let mut responses = responses.into_iter();
+        let connections = responses.len();
+        let (port, handle) = test_http::serve(connections, move |_| {
+            let (status, body) = responses.next().unwrap();
+            Response::json(status, body)
+        });
+        (format!("http://127.0.0.1:{port}/token"), handle)
+    }
+
+    fn request_bodies(server: std::thread::JoinHandle<Vec<ReceivedRequest>>) -> Vec<String> {
+        server
+            .join()
+            .unwrap()
+            .into_iter()
+            .map(|request| String::from_utf8(request.body).unwrap())
+            .collect()
+    }
+
+    fn oauth_config(token_url: &str) -> OAuthConfigSchema {
+        OAuthConfigSchema {
+            token_url: token_url.to_string(),
+            client_id: Some("client-1".to_string()),
+            access_token_field: "access_token".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exchanges_refresh_token_and_caches_access_token() {
+        let (url, server) = spawn_token_server(vec![(
+            200,
+            json!({ "access_token": "at-1", "expires_in": 3600 }).to_string(),
+        )]);
+        let cache = OAuthTokenCache::default();
+        let oauth = oauth_config(&url);
+
+        let token = cache.access_token("k", &oauth, || Some("rt-1".to_string()));
+        assert_eq!(token, Ok(Some("at-1".to_string())));
+
+        // Served from the cache; the server only answers once.
+        let token = cache.access_token("k", &oauth, || panic!("refresh token not needed"));
+        assert_eq!(token, Ok(Some("at-1".to_string())));
+
+        let bodies = request_bodies(server);
+        assert_eq!(
+            bodies,
+            vec!["grant_type=refresh_token&refresh_token=rt-1&client_id=client-1"]
+        );
+    }
+
+    #[test]
+    fn refreshes_expired_token_with_rotated_refresh_token() {
+        let (url, server) = spawn_token_server(vec![
+            (
+                200,
+                // Within the expiry skew, so stale as soon as it is cached.
+                json!({ "access_token": "at-1", "expires_in": 5, "refresh_token": "rt-2" })
+                    .to_string(),
+            ),
+            (200, json!({ "access_token": "at-2" }).to_string()),
+        ]);
+        let cache = OAuthTokenCache::default();
+        let oauth = oauth_config(&url);
+
+        let first = cache.access_token("k", &oauth, || Some("rt-1".to_string()));
+        assert_eq!(first, Ok(Some("at-1".to_string())));
+
+        let second = cache.access_token("k", &oauth, || Some("rt-1".to_string()));
+        assert_eq!(second, Ok(Some("at-2".to_string())));
+
+        let bodies = request_bodies(server);
+        assert!(
+            bodies[0].contains("refresh_token=rt-1"),
+            "body: {}",
+            bodies[0]
+        );
+        assert!(
+            bodies[1].contains("refresh_token=rt-2"),
+            "body: {}",
+            bodies[1]
+        );
+    }
+
+    #[test]
+    fn reports_token_endpoint_errors_without_the_body() {
+        let (url, server) = spawn_token_server(vec![(
+            400,
+            json!({
+                "error": "invalid_grant",
+                "error_description": "refresh token revoked",
+                "refresh_token": "rt-1"
+            })
+            .to_string(),
+        )]);
+        let cache = OAuthTokenCache::default();
+
+        let err = cache
+            .access_token("k", &oauth_config(&url), || Some("rt-1".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "oauth token refresh failed: token endpoint returned 400: invalid_grant (refresh token revoked)"
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn missing_refresh_token_defers_to_the_plain_secret() {
+        let cache = OAuthTokenCache::default();
+        let token = cache.access_token("k", &oauth_config("http://127.0.0.1:9/token"), || None);
+        assert_eq!(token, Ok(None));
+    }
+
+    #[test]
+    fn parse_token_response_reads_nested_access_token_field() {
+        let body = json!({ "authed_user": { "access_token": "xoxp-1" }, "expires_in": "60" });
+        let token = parse_token_response(&body, "authed_user.access_token").unwrap();
+        assert_eq!(token.access_token, "xoxp-1");
+        assert!(token.expires_at.is_some());
+
+        let err = parse_token_response(&json!({ "token": "x" }), "access_token").unwrap_err();
+        assert_eq!(err, "oauth token response has no 'access_token' field");
+    }
+}
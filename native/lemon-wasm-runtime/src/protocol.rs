@@ -7,17 +7,25 @@ pub enum Request {
     Hello {
         id: String,
         version: Option<u32>,
+        #[serde(default = "default_strict")]
+        strict: bool,
     },
     Discover {
         id: String,
         paths: Vec<String>,
         defaults: DiscoverDefaults,
+        /// Only compile each `.wasm` and report pass/fail per file, leaving
+        /// the loaded tools untouched. Answered with a [`CompileCheckResult`].
+        #[serde(default)]
+        compile_only: bool,
     },
     Invoke {
         id: String,
         tool: String,
         params_json: String,
         context_json: Option<String>,
+        #[serde(default)]
+        max_depth: Option<u32>,
     },
     HostCallResult {
         id: String,
@@ -26,11 +34,22 @@ pub enum Request {
         output_json: Option<String>,
         error: Option<String>,
     },
+    ValidateCapabilities {
+        id: String,
+        capabilities_json: String,
+    },
+    Status {
+        id: String,
+    },
     Shutdown {
         id: String,
     },
 }
 
+fn default_strict() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DiscoverDefaults {
     pub default_memory_limit: u64,
@@ -39,6 +58,22 @@ pub struct DiscoverDefaults {
     pub cache_compiled: bool,
     pub cache_dir: Option<String>,
     pub max_tool_invoke_depth: u32,
+    #[serde(default)]
+    pub trace_capabilities: bool,
+    #[serde(default)]
+    pub max_resident_components: Option<usize>,
+    #[serde(default)]
+    pub max_host_calls: Option<u32>,
+    #[serde(default)]
+    pub offline: bool,
+    #[serde(default)]
+    pub allow_unredacted_debug: bool,
+    #[serde(default)]
+    pub forbid_binary_output: bool,
+    #[serde(default)]
+    pub rng_seed: Option<u64>,
+    #[serde(default)]
+    pub max_events_per_invoke: Option<u32>,
 }
 
 impl Default for DiscoverDefaults {
@@ -50,6 +85,14 @@ impl Default for DiscoverDefaults {
             cache_compiled: true,
             cache_dir: None,
             max_tool_invoke_depth: 4,
+            trace_capabilities: false,
+            max_resident_components: None,
+            max_host_calls: None,
+            offline: false,
+            allow_unredacted_debug: false,
+            forbid_binary_output: false,
+            rng_seed: None,
+            max_events_per_invoke: None,
         }
     }
 }
@@ -62,6 +105,8 @@ pub struct ToolCapabilitiesSummary {
     pub secrets: bool,
     pub auth: bool,
     pub exec: bool,
+    #[serde(default)]
+    pub exec_idempotent: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,9 +130,24 @@ pub struct DiscoveredTool {
     pub capabilities: ToolCapabilitiesSummary,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth: Option<DiscoveredToolAuth>,
+    #[serde(default)]
+    pub effects: ToolEffects,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub warnings: Vec<String>,
 }
 
+/// What invoking a tool may affect, declared by `x-effects` in its schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolEffects {
+    Pure,
+    Readonly,
+    /// May change external state. Assumed when a tool declares nothing.
+    #[default]
+    Mutating,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoverResult {
     pub tools: Vec<DiscoveredTool>,
@@ -95,12 +155,53 @@ pub struct DiscoverResult {
     pub errors: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileCheck {
+    pub path: String,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileCheckResult {
+    pub files: Vec<CompileCheck>,
+    pub warnings: Vec<String>,
+}
+
+/// Findings for a capabilities file. `errors` are settings that can never
+/// take effect (or a file that doesn't parse); `warnings` are likely
+/// mistakes that still load.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapabilitiesValidation {
+    pub valid: bool,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvokeResult {
     pub output_json: Option<String>,
     pub error: Option<String>,
     pub logs: Vec<RuntimeLog>,
     pub details: Value,
+    /// Host call outputs before secret redaction; only present when the
+    /// runtime sets `allow_unredacted_debug`. Never passed to WASM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unredacted_debug: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Set whenever `error` is: who the error came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ErrorKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Tool,
+    /// The runtime stopped the tool: fuel exhaustion, timeout, or a trap.
+    Runtime,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +226,16 @@ pub enum OutboundMessage {
         call_id: String,
         tool: String,
         params_json: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        idempotency_key: Option<String>,
+    },
+    /// Sent as `type: "event"` like host calls; hosts skip events they
+    /// don't handle.
+    #[serde(rename = "event")]
+    Notice {
+        event: String,
+        request_id: String,
+        message: String,
     },
 }
 
@@ -169,8 +280,10 @@ mod tests {
                 id,
                 paths,
                 defaults,
+                compile_only,
             } => {
                 assert_eq!(id, "req_1");
+                assert!(!compile_only);
                 assert_eq!(paths, vec!["/tmp/tools"]);
                 assert_eq!(defaults.default_memory_limit, 10 * 1024 * 1024);
             }
@@ -178,6 +291,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_capabilities_request_decodes() {
+        let encoded = serde_json::json!({
+            "type": "validate_capabilities",
+            "id": "req_3",
+            "capabilities_json": "{}"
+        })
+        .to_string();
+
+        match serde_json::from_str(&encoded).expect("decode request") {
+            Request::ValidateCapabilities {
+                id,
+                capabilities_json,
+            } => {
+                assert_eq!(id, "req_3");
+                assert_eq!(capabilities_json, "{}");
+            }
+            other => panic!("unexpected request variant: {other:?}"),
+        }
+    }
+
     #[test]
     fn response_message_roundtrips() {
         let message = OutboundMessage::response_ok("req_2", serde_json::json!({"ok": true}));
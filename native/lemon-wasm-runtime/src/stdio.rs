@@ -0,0 +1,104 @@
+//! In-memory WASI stdout/stderr for tools that declare `capture_stdio`.
+//!
+//! Without it, anything a tool prints through WASI is discarded. Captured
+//! output is bounded: writes past the capacity are dropped rather than
+//! failing, so a chatty tool can't trap itself by printing.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use wasmtime_wasi::{HostOutputStream, StdoutStream, StreamError, Subscribe};
+
+/// Permit handed out by `check_write`; writes are never refused.
+const WRITE_PERMIT: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct CapturedOutput {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    truncated: Arc<AtomicBool>,
+    capacity: usize,
+}
+
+impl CapturedOutput {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::default(),
+            truncated: Arc::default(),
+            capacity,
+        }
+    }
+
+    /// Returns everything written so far and whether any of it was dropped,
+    /// leaving the buffer empty.
+    pub fn take(&self) -> (Vec<u8>, bool) {
+        let contents = self
+            .buffer
+            .lock()
+            .map(|mut buffer| std::mem::take(&mut *buffer))
+            .unwrap_or_default();
+        (contents, self.truncated.swap(false, Ordering::Relaxed))
+    }
+}
+
+impl HostOutputStream for CapturedOutput {
+    fn write(&mut self, bytes: Bytes) -> Result<(), StreamError> {
+        let mut buffer = self
+            .buffer
+            .lock()
+            .map_err(|_| StreamError::trap("captured output lock poisoned"))?;
+
+        let room = self.capacity.saturating_sub(buffer.len());
+        if bytes.len() > room {
+            self.truncated.store(true, Ordering::Relaxed);
+        }
+        buffer.extend_from_slice(&bytes[..bytes.len().min(room)]);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), StreamError> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> Result<usize, StreamError> {
+        Ok(WRITE_PERMIT)
+    }
+}
+
+#[wasmtime_wasi::async_trait]
+impl Subscribe for CapturedOutput {
+    async fn ready(&mut self) {}
+}
+
+impl StdoutStream for CapturedOutput {
+    fn stream(&self) -> Box<dyn HostOutputStream> {
+        Box::new(self.clone())
+    }
+
+    fn isatty(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use pretty_assertions::assert_eq;
+    use wasmtime_wasi::StdoutStream;
+
+    use super::CapturedOutput;
+
+    #[test]
+    fn captures_writes_across_streams_and_drops_overflow() {
+        let output = CapturedOutput::new(8);
+        let mut first = output.stream();
+        let mut second = output.stream();
+
+        first.write(Bytes::from_static(b"hello")).unwrap();
+        second.write(Bytes::from_static(b" world")).unwrap();
+        assert_eq!(first.check_write().unwrap(), super::WRITE_PERMIT);
+
+        assert_eq!(output.take(), (b"hello wo".to_vec(), true));
+        assert_eq!(output.take(), (Vec::new(), false));
+    }
+}
@@ -0,0 +1,105 @@
+//! A one-connection-per-response HTTP/1.1 server for tests.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReceivedRequest {
+    /// The request line and header lines as sent, CRLF included.
+    pub head: String,
+    pub body: Vec<u8>,
+}
+
+impl ReceivedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(status, body).header("content-type", "application/json")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        assert!(
+            !format!("{name}{value}").contains(['\r', '\n']),
+            "add each header with its own call"
+        );
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Accepts `connections` connections in turn, answering each request with
+/// `respond`. Returns the port and a handle yielding the requests received.
+pub fn serve<F>(connections: usize, mut respond: F) -> (u16, JoinHandle<Vec<ReceivedRequest>>)
+where
+    F: FnMut(&ReceivedRequest) -> Response + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = std::thread::spawn(move || {
+        let mut received = Vec::new();
+
+        for _ in 0..connections {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = ReceivedRequest::default();
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                request.head.push_str(&line);
+            }
+            let content_length = request
+                .header("content-length")
+                .map_or(0, |length| length.parse().unwrap());
+            request.body = vec![0; content_length];
+            reader.read_exact(&mut request.body).unwrap();
+
+            let response = respond(&request);
+            let mut head = format!("HTTP/1.1 {} X\r\n", response.status);
+            for (name, value) in &response.headers {
+                head.push_str(&format!("{name}: {value}\r\n"));
+            }
+            head.push_str(&format!(
+                "content-length: {}\r\nconnection: close\r\n\r\n",
+                response.body.len()
+            ));
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&response.body).unwrap();
+
+            received.push(request);
+        }
+
+        received
+    });
+
+    (port, handle)
+}
@@ -0,0 +1,349 @@
+use alloy_json_abi::{AbiItem, JsonAbi, Param, StateMutability};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use wasm_tools_common::{parse_params, typed_params};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct AbiConvertTool;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AbiConvertParams {
+    op: Op,
+    abi: Value,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Op {
+    ToHuman,
+    ToJson,
+}
+
+impl Guest for AbiConvertTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "abi_convert",
+            "x-effects": "pure",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "op": {
+                    "type": "string",
+                    "enum": ["to_human", "to_json"],
+                    "description": "'to_human' turns a JSON ABI into human-readable signatures; 'to_json' does the reverse"
+                },
+                "abi": {
+                    "description": "For 'to_human': a JSON ABI array, a compiler artifact with an 'abi' field, or either as a JSON string. \
+                                    For 'to_json': an array of human-readable signatures (e.g. \"function transfer(address to, uint256 amount) returns (bool)\") or a newline-separated string of them."
+                }
+            },
+            "required": ["op", "abi"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Convert a contract ABI between the JSON ABI format and human-readable signatures \
+         (functions, events, errors, constructor). Runs locally; no RPC or secrets needed."
+            .to_string()
+    }
+}
+
+export!(AbiConvertTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    let params: AbiConvertParams = typed_params(&parse_params(params_raw)?)?;
+
+    let abi = match params.op {
+        Op::ToHuman => json!(to_human(&parse_json_abi(&params.abi)?)),
+        Op::ToJson => json!(parse_human_abi(&params.abi)?),
+    };
+
+    Ok(json!({ "abi": abi }).to_string())
+}
+
+/// Accepts the ABI array itself, a Foundry/Hardhat artifact wrapping it in
+/// `abi`, or either one encoded as a string.
+fn parse_json_abi(input: &Value) -> Result<JsonAbi, String> {
+    let value = match input {
+        Value::String(raw) => {
+            serde_json::from_str(raw).map_err(|err| format!("'abi' is not valid JSON: {}", err))?
+        }
+        other => other.clone(),
+    };
+
+    let abi = match value {
+        Value::Object(mut artifact) if artifact.contains_key("abi") => artifact.remove("abi"),
+        value => Some(value),
+    };
+
+    serde_json::from_value(abi.unwrap_or(Value::Null))
+        .map_err(|err| format!("invalid JSON ABI: {}", err))
+}
+
+fn parse_human_abi(input: &Value) -> Result<JsonAbi, String> {
+    let signatures: Vec<&str> = match input {
+        Value::String(raw) => raw.lines().collect(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .ok_or_else(|| "'abi' entries must be signature strings".to_string())
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err("'abi' must be an array of signatures or a string".to_string()),
+    };
+
+    let signatures: Vec<&str> = signatures
+        .into_iter()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if signatures.is_empty() {
+        return Err("'abi' has no signatures".to_string());
+    }
+
+    let mut abi = JsonAbi::new();
+    for signature in signatures {
+        let item = AbiItem::parse(signature)
+            .map_err(|err| format!("invalid signature '{}': {}", signature, err))?;
+        match item {
+            AbiItem::Constructor(item) => abi.constructor = Some(item.into_owned()),
+            AbiItem::Function(item) => {
+                let item = item.into_owned();
+                abi.functions
+                    .entry(item.name.clone())
+                    .or_default()
+                    .push(item);
+            }
+            AbiItem::Event(item) => {
+                let item = item.into_owned();
+                abi.events.entry(item.name.clone()).or_default().push(item);
+            }
+            AbiItem::Error(item) => {
+                let item = item.into_owned();
+                abi.errors.entry(item.name.clone()).or_default().push(item);
+            }
+            AbiItem::Fallback(_) | AbiItem::Receive(_) => {
+                return Err(format!("unsupported signature '{}'", signature));
+            }
+        }
+    }
+
+    Ok(abi)
+}
+
+fn to_human(abi: &JsonAbi) -> Vec<String> {
+    abi.items()
+        .map(|item| match item {
+            AbiItem::Constructor(item) => {
+                format!(
+                    "constructor{}{}",
+                    params_list(&item.inputs),
+                    mutability_suffix(item.state_mutability)
+                )
+            }
+            AbiItem::Fallback(item) => {
+                format!(
+                    "fallback() external{}",
+                    mutability_suffix(item.state_mutability)
+                )
+            }
+            AbiItem::Receive(_) => "receive() external payable".to_string(),
+            AbiItem::Function(item) => item.full_signature(),
+            AbiItem::Event(item) => item.full_signature(),
+            AbiItem::Error(item) => format!("error {}{}", item.name, params_list(&item.inputs)),
+        })
+        .collect()
+}
+
+/// `(type name, ...)`, with tuple components spelled out.
+fn params_list(params: &[Param]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|param| {
+            let mut ty = String::new();
+            param.full_selector_type_raw(&mut ty);
+            if param.name.is_empty() {
+                ty
+            } else {
+                format!("{} {}", ty, param.name)
+            }
+        })
+        .collect();
+    format!("({})", params.join(", "))
+}
+
+fn mutability_suffix(state_mutability: StateMutability) -> &'static str {
+    match state_mutability {
+        StateMutability::Payable => " payable",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn erc20_json_abi() -> Value {
+        json!([
+            {
+                "type": "constructor",
+                "inputs": [{ "name": "supply", "type": "uint256" }],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [
+                    { "name": "to", "type": "address" },
+                    { "name": "amount", "type": "uint256" }
+                ],
+                "outputs": [{ "name": "", "type": "bool" }],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "type": "function",
+                "name": "balanceOf",
+                "inputs": [{ "name": "owner", "type": "address" }],
+                "outputs": [{ "name": "", "type": "uint256" }],
+                "stateMutability": "view"
+            },
+            {
+                "type": "event",
+                "name": "Transfer",
+                "inputs": [
+                    { "name": "from", "type": "address", "indexed": true },
+                    { "name": "to", "type": "address", "indexed": true },
+                    { "name": "value", "type": "uint256", "indexed": false }
+                ],
+                "anonymous": false
+            },
+            {
+                "type": "error",
+                "name": "InsufficientBalance",
+                "inputs": [
+                    { "name": "needed", "type": "uint256" },
+                    { "name": "available", "type": "uint256" }
+                ]
+            }
+        ])
+    }
+
+    fn convert(params: Value) -> Result<Value, String> {
+        execute_impl(&params.to_string()).map(|out| serde_json::from_str(&out).unwrap())
+    }
+
+    #[test]
+    fn to_human_lists_every_item() {
+        let out = convert(json!({ "op": "to_human", "abi": erc20_json_abi() })).unwrap();
+
+        let mut signatures: Vec<&str> = out["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s.as_str().unwrap())
+            .collect();
+        signatures.sort();
+
+        assert_eq!(
+            signatures,
+            vec![
+                "constructor(uint256 supply)",
+                "error InsufficientBalance(uint256 needed, uint256 available)",
+                "event Transfer(address indexed from, address indexed to, uint256 value)",
+                "function balanceOf(address owner) view returns (uint256)",
+                "function transfer(address to, uint256 amount) returns (bool)",
+            ]
+        );
+    }
+
+    #[test]
+    fn to_json_round_trips_human_signatures() {
+        let human = convert(json!({ "op": "to_human", "abi": erc20_json_abi() })).unwrap();
+        let out = convert(json!({ "op": "to_json", "abi": human["abi"] })).unwrap();
+
+        let expected: JsonAbi = serde_json::from_value(erc20_json_abi()).unwrap();
+        let converted: JsonAbi = serde_json::from_value(out["abi"].clone()).unwrap();
+        assert_eq!(converted, expected);
+    }
+
+    #[test]
+    fn to_json_accepts_newline_separated_signatures() {
+        let out = convert(json!({
+            "op": "to_json",
+            "abi": "function approve(address spender, uint256 amount) returns (bool)\n\n\
+                    event Approval(address indexed owner, address indexed spender, uint256 value)\n"
+        }))
+        .unwrap();
+
+        let abi = out["abi"].as_array().unwrap();
+        assert_eq!(abi.len(), 2);
+        assert_eq!(abi[0]["type"], "function");
+        assert_eq!(abi[0]["name"], "approve");
+        assert_eq!(abi[0]["inputs"][1]["type"], "uint256");
+        assert_eq!(abi[1]["type"], "event");
+        assert_eq!(abi[1]["inputs"][0]["indexed"], true);
+    }
+
+    #[test]
+    fn to_human_reads_artifacts_and_json_strings() {
+        let artifact = json!({ "abi": erc20_json_abi(), "bytecode": "0x" }).to_string();
+        let out = convert(json!({ "op": "to_human", "abi": artifact })).unwrap();
+        assert_eq!(out["abi"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let err = convert(json!({ "op": "to_human", "abi": "not json" })).unwrap_err();
+        assert!(err.starts_with("'abi' is not valid JSON"), "{err}");
+
+        let err =
+            convert(json!({ "op": "to_human", "abi": [{ "type": "function" }] })).unwrap_err();
+        assert!(err.starts_with("invalid JSON ABI"), "{err}");
+
+        let err = convert(json!({ "op": "to_json", "abi": ["function (uint"] })).unwrap_err();
+        assert!(
+            err.starts_with("invalid signature 'function (uint'"),
+            "{err}"
+        );
+
+        let err = convert(json!({ "op": "to_json", "abi": [] })).unwrap_err();
+        assert_eq!(err, "'abi' has no signatures");
+
+        let err = convert(json!({ "op": "to_yaml", "abi": [] })).unwrap_err();
+        assert!(err.contains("unknown variant `to_yaml`"), "{err}");
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema: Value = serde_json::from_str(&AbiConvertTool::schema()).expect("valid JSON");
+        assert_eq!(schema["title"], "abi_convert");
+        assert_eq!(schema["required"], json!(["op", "abi"]));
+        assert_eq!(
+            schema["properties"]["op"]["enum"],
+            json!(["to_human", "to_json"])
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use serde_json::{Value, json};
+use wasm_tools_common::{RPC_URL_DESCRIPTION, execute_command_tool, required_string};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct CastBlockTool;
+
+impl Guest for CastBlockTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "cast_block",
+            "x-effects": "readonly",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "block": {
+                    "type": "string",
+                    "description": "Block number, hash, or tag (e.g. 'latest', 'finalized', '19000000'). Defaults to 'latest'."
+                },
+                "rpc_url": {
+                    "type": "string",
+                    "description": RPC_URL_DESCRIPTION
+                },
+                "field": {
+                    "type": "string",
+                    "description": "Return only this block field (e.g. 'timestamp', 'baseFeePerGas', 'gasUsed')"
+                }
+            },
+            "required": ["rpc_url"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Get block metadata (timestamp, base fee, gas used, hash, ...) using `cast block`. \
+         Read-only; no private key is needed. Set `field` to return a single value."
+            .to_string()
+    }
+}
+
+export!(CastBlockTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    execute_command_tool(
+        params_raw,
+        build_args,
+        "cast",
+        30_000,
+        "cast block",
+        "block",
+    )
+}
+
+fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    let rpc_url = required_string(params, "rpc_url")?;
+    let block = params["block"].as_str().unwrap_or("latest");
+
+    // Both are positional, so a leading '-' would be parsed as a flag.
+    if block.is_empty() || block.starts_with('-') {
+        return Err(format!("invalid 'block': {}", block));
+    }
+
+    let mut args: Vec<String> = vec!["block".to_string(), block.to_string()];
+
+    if let Some(field) = params["field"].as_str() {
+        if field.is_empty()
+            || !field
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            return Err(format!("invalid 'field': {}", field));
+        }
+        args.push(field.to_string());
+    }
+
+    args.push("--rpc-url".to_string());
+    args.push(rpc_url.to_string());
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn build_args_defaults_to_latest_block() {
+        let params = json!({ "rpc_url": "https://eth.llamarpc.com" });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec!["block", "latest", "--rpc-url", "https://eth.llamarpc.com"]
+        );
+    }
+
+    #[test]
+    fn build_args_with_block_and_field() {
+        let params = json!({
+            "block": "19000000",
+            "rpc_url": "https://rpc.example.com",
+            "field": "baseFeePerGas"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "block",
+                "19000000",
+                "baseFeePerGas",
+                "--rpc-url",
+                "https://rpc.example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_args_rejects_flag_like_positionals() {
+        let params = json!({ "block": "--json", "rpc_url": "https://rpc.example.com" });
+        assert!(build_args(&params).is_err());
+
+        let params = json!({ "field": "--rpc-url", "rpc_url": "https://rpc.example.com" });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_has_no_secrets() {
+        let params = json!({ "rpc_url": "https://rpc.example.com", "field": "timestamp" });
+
+        let args = build_args(&params).unwrap();
+        assert!(!args.iter().any(|a| a.contains("SECRET")));
+        assert!(!args.iter().any(|a| a.contains("private-key")));
+    }
+
+    #[test]
+    fn build_args_rejects_missing_rpc_url() {
+        let params = json!({ "block": "latest" });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema_str = CastBlockTool::schema();
+        let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
+        assert_eq!(schema["title"], "cast_block");
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], json!(["rpc_url"]));
+        assert!(schema["properties"]["field"].is_object());
+    }
+}
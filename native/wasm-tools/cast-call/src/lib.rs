@@ -1,6 +1,8 @@
-use serde_json::{Value, json};
+use serde::Deserialize;
+use serde_json::json;
 use wasm_tools_common::{
-    append_string_array, execute_command_tool, required_string, validate_address,
+    RPC_URL_DESCRIPTION, apply_context_chain, execute_command, parse_params, run_preflight,
+    typed_params, validate_address, with_details,
 };
 
 wit_bindgen::generate!({
@@ -12,9 +14,27 @@ use exports::near::agent::tool::{Guest, Request, Response};
 
 struct CastCallTool;
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CastCallParams {
+    to: String,
+    sig: String,
+    #[serde(default)]
+    args: Vec<String>,
+    rpc_url: String,
+    chain: Option<String>,
+    block: Option<String>,
+    /// Accepted for schema compatibility; `cast call` already decodes the
+    /// return value when `sig` declares return types.
+    #[serde(default, rename = "decode")]
+    _decode: bool,
+    #[serde(default)]
+    preflight: bool,
+}
+
 impl Guest for CastCallTool {
     fn execute(req: Request) -> Response {
-        match execute_impl(&req.params) {
+        match execute_impl(&req.params, req.context.as_deref()) {
             Ok(output) => Response {
                 output: Some(output),
                 error: None,
@@ -29,6 +49,7 @@ impl Guest for CastCallTool {
     fn schema() -> String {
         json!({
             "title": "cast_call",
+            "x-effects": "readonly",
             "type": "object",
             "additionalProperties": false,
             "properties": {
@@ -47,16 +68,20 @@ impl Guest for CastCallTool {
                 },
                 "rpc_url": {
                     "type": "string",
-                    "description": "JSON-RPC endpoint URL"
+                    "description": RPC_URL_DESCRIPTION
                 },
                 "chain": {
                     "type": "string",
-                    "description": "Chain name or ID (e.g. 'mainnet', '1', 'sepolia')"
+                    "description": "Chain name or ID (e.g. 'mainnet', '1', 'sepolia'). Defaults to the invoke context's chain_id when omitted."
                 },
                 "block": {
                     "type": "string",
                     "description": "Block number or tag (e.g. 'latest', 'pending', a number)"
                 },
+                "preflight": {
+                    "type": "boolean",
+                    "description": "First record `cast chain-id` and `cast client` for rpc_url in the result details"
+                },
                 "decode": {
                     "type": "boolean",
                     "description": "Attempt to ABI-decode the return value"
@@ -76,32 +101,45 @@ impl Guest for CastCallTool {
 
 export!(CastCallTool);
 
-fn execute_impl(params_raw: &str) -> Result<String, String> {
-    execute_command_tool(params_raw, build_args, "cast", 30_000, "cast call", "output")
-}
+fn execute_impl(params_raw: &str, context_raw: Option<&str>) -> Result<String, String> {
+    let mut params = parse_params(params_raw)?;
+    apply_context_chain(&mut params, context_raw);
 
-fn build_args(params: &Value) -> Result<Vec<String>, String> {
-    let to = required_string(params, "to")?;
-    let sig = required_string(params, "sig")?;
-    let rpc_url = required_string(params, "rpc_url")?;
+    let params: CastCallParams = typed_params(&params)?;
+    let args = build_args(&params)?;
 
-    validate_address(to)?;
+    let details = if params.preflight {
+        Some(run_preflight(&params.rpc_url)?)
+    } else {
+        None
+    };
+
+    let output = execute_command("cast", &args, 30_000, "cast call", "output")?;
+
+    match details {
+        Some(details) => with_details(&output, details),
+        None => Ok(output),
+    }
+}
 
-    let mut args: Vec<String> = vec!["call".to_string(), to.to_string(), sig.to_string()];
+fn build_args(params: &CastCallParams) -> Result<Vec<String>, String> {
+    validate_address(&params.to)?;
 
-    append_string_array(&mut args, params, "args")?;
+    let mut args: Vec<String> = vec!["call".to_string(), params.to.clone(), params.sig.clone()];
+
+    args.extend(params.args.iter().cloned());
 
     args.push("--rpc-url".to_string());
-    args.push(rpc_url.to_string());
+    args.push(params.rpc_url.clone());
 
-    if let Some(chain) = params["chain"].as_str() {
+    if let Some(chain) = &params.chain {
         args.push("--chain".to_string());
-        args.push(chain.to_string());
+        args.push(chain.clone());
     }
 
-    if let Some(block) = params["block"].as_str() {
+    if let Some(block) = &params.block {
         args.push("--block".to_string());
-        args.push(block.to_string());
+        args.push(block.clone());
     }
 
     Ok(args)
@@ -110,7 +148,11 @@ fn build_args(params: &Value) -> Result<Vec<String>, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use serde_json::{Value, json};
+
+    fn build_args_from(params: &Value) -> Result<Vec<String>, String> {
+        build_args(&typed_params(params)?)
+    }
 
     #[test]
     fn build_args_minimal() {
@@ -120,7 +162,7 @@ mod tests {
             "rpc_url": "https://eth.llamarpc.com"
         });
 
-        let args = build_args(&params).unwrap();
+        let args = build_args_from(&params).unwrap();
         assert_eq!(
             args,
             vec![
@@ -144,7 +186,7 @@ mod tests {
             "block": "latest"
         });
 
-        let args = build_args(&params).unwrap();
+        let args = build_args_from(&params).unwrap();
         assert!(args.contains(&"0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string()));
         assert!(args.contains(&"--chain".to_string()));
         assert!(args.contains(&"--block".to_string()));
@@ -159,7 +201,7 @@ mod tests {
             "rpc_url": "https://rpc.example.com"
         });
 
-        let args = build_args(&params).unwrap();
+        let args = build_args_from(&params).unwrap();
         // cast_call is read-only, no --private-key
         assert!(!args.iter().any(|a| a.contains("private-key")));
         assert!(!args.iter().any(|a| a.contains("SECRET")));
@@ -171,7 +213,94 @@ mod tests {
             "to": "0x1234567890abcdef1234567890abcdef12345678",
             "rpc_url": "https://rpc.example.com"
         });
-        assert!(build_args(&params).is_err());
+        assert!(build_args_from(&params).is_err());
+    }
+
+    #[test]
+    fn context_chain_id_flows_into_args() {
+        let mut params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "balanceOf(address)",
+            "rpc_url": "https://rpc.example.com"
+        });
+        apply_context_chain(&mut params, Some(r#"{"cwd":"/tmp","chain_id":8453}"#));
+
+        let args = build_args_from(&params).unwrap();
+        let chain_pos = args.iter().position(|arg| arg == "--chain").unwrap();
+        assert_eq!(args[chain_pos + 1], "8453");
+    }
+
+    #[test]
+    fn explicit_chain_overrides_context_chain_id() {
+        let mut params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "balanceOf(address)",
+            "rpc_url": "https://rpc.example.com",
+            "chain": "sepolia"
+        });
+        apply_context_chain(&mut params, Some(r#"{"chain_id":1}"#));
+
+        let args = build_args_from(&params).unwrap();
+        assert!(args.contains(&"sepolia".to_string()));
+        assert!(!args.contains(&"1".to_string()));
+    }
+
+    #[test]
+    fn typed_params_reject_wrong_types() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "balanceOf(address)",
+            "rpc_url": "https://rpc.example.com",
+            "block": 123
+        });
+        let err = build_args_from(&params).unwrap_err();
+        assert!(
+            err.starts_with("invalid param 'block': invalid type: integer `123`"),
+            "{err}"
+        );
+
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "balanceOf(address)",
+            "rpc_url": "https://rpc.example.com",
+            "args": [1]
+        });
+        let err = build_args_from(&params).unwrap_err();
+        assert!(err.starts_with("invalid param 'args[0]'"), "{err}");
+    }
+
+    #[test]
+    fn typed_params_reject_unknown_fields() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "balanceOf(address)",
+            "rpc_url": "https://rpc.example.com",
+            "gas": "1"
+        });
+        assert!(
+            build_args_from(&params)
+                .unwrap_err()
+                .contains("unknown field `gas`")
+        );
+    }
+
+    #[test]
+    fn preflight_param_parses_without_changing_args() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "totalSupply()",
+            "rpc_url": "https://rpc.example.com",
+            "preflight": true
+        });
+
+        let typed: CastCallParams = typed_params(&params).unwrap();
+        assert!(typed.preflight);
+        assert!(
+            !build_args(&typed)
+                .unwrap()
+                .contains(&"--preflight".to_string())
+        );
+        assert!(CastCallTool::schema().contains("\"preflight\""));
     }
 
     #[test]
@@ -179,8 +308,23 @@ mod tests {
         let schema_str = CastCallTool::schema();
         let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
         assert_eq!(schema["title"], "cast_call");
-        assert!(schema["required"].as_array().unwrap().contains(&json!("to")));
-        assert!(schema["required"].as_array().unwrap().contains(&json!("sig")));
-        assert!(schema["required"].as_array().unwrap().contains(&json!("rpc_url")));
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("to"))
+        );
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("sig"))
+        );
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("rpc_url"))
+        );
     }
 }
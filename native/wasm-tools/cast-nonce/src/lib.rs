@@ -0,0 +1,165 @@
+use serde_json::{Value, json};
+use wasm_tools_common::{
+    RPC_URL_DESCRIPTION, execute_command_tool, required_string, validate_address,
+};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct CastNonceTool;
+
+impl Guest for CastNonceTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "cast_nonce",
+            "x-effects": "readonly",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Account address to look up (0x-prefixed hex)"
+                },
+                "rpc_url": {
+                    "type": "string",
+                    "description": RPC_URL_DESCRIPTION
+                },
+                "block": {
+                    "type": "string",
+                    "description": "Block number or tag (e.g. 'latest', 'pending', a number)"
+                }
+            },
+            "required": ["address", "rpc_url"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Get the current transaction count (nonce) of an Ethereum account using `cast nonce`. \
+         Read-only; no private key is needed. Use it to sequence nonces for batched sends."
+            .to_string()
+    }
+}
+
+export!(CastNonceTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    execute_command_tool(
+        params_raw,
+        build_args,
+        "cast",
+        30_000,
+        "cast nonce",
+        "nonce",
+    )
+}
+
+fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    let address = required_string(params, "address")?;
+    let rpc_url = required_string(params, "rpc_url")?;
+
+    validate_address(address)?;
+
+    let mut args: Vec<String> = vec!["nonce".to_string(), address.to_string()];
+
+    args.push("--rpc-url".to_string());
+    args.push(rpc_url.to_string());
+
+    if let Some(block) = params["block"].as_str() {
+        args.push("--block".to_string());
+        args.push(block.to_string());
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn build_args_minimal() {
+        let params = json!({
+            "address": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://eth.llamarpc.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "nonce",
+                "0x1234567890abcdef1234567890abcdef12345678",
+                "--rpc-url",
+                "https://eth.llamarpc.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_args_with_block() {
+        let params = json!({
+            "address": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "block": "pending"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(&args[args.len() - 2..], ["--block", "pending"]);
+    }
+
+    #[test]
+    fn build_args_has_no_secrets() {
+        let params = json!({
+            "address": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert!(!args.iter().any(|a| a.contains("SECRET")));
+        assert!(!args.iter().any(|a| a.contains("private-key")));
+    }
+
+    #[test]
+    fn build_args_rejects_invalid_address() {
+        let params = json!({
+            "address": "0x1234",
+            "rpc_url": "https://rpc.example.com"
+        });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_rejects_missing_rpc_url() {
+        let params = json!({ "address": "0x1234567890abcdef1234567890abcdef12345678" });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema_str = CastNonceTool::schema();
+        let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
+        assert_eq!(schema["title"], "cast_nonce");
+        assert_eq!(schema["type"], "object");
+        assert!(schema["properties"]["address"].is_object());
+        assert!(schema["properties"]["rpc_url"].is_object());
+    }
+}
@@ -0,0 +1,155 @@
+use serde_json::{Value, json};
+use wasm_tools_common::{RPC_URL_DESCRIPTION, execute_command_tool, required_string};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct CastPublishTool;
+
+impl Guest for CastPublishTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "cast_publish",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "raw_tx": {
+                    "type": "string",
+                    "description": "The signed, RLP-encoded transaction to broadcast (0x-prefixed hex)"
+                },
+                "rpc_url": {
+                    "type": "string",
+                    "description": RPC_URL_DESCRIPTION
+                }
+            },
+            "required": ["raw_tx", "rpc_url"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Broadcast a pre-signed raw transaction using `cast publish` and return its hash. \
+         The transaction must already be signed (e.g. offline), so no private key is needed."
+            .to_string()
+    }
+}
+
+export!(CastPublishTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    execute_command_tool(
+        params_raw,
+        build_args,
+        "cast",
+        60_000,
+        "cast publish",
+        "tx_hash",
+    )
+}
+
+fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    let raw_tx = required_string(params, "raw_tx")?;
+    let rpc_url = required_string(params, "rpc_url")?;
+
+    validate_raw_tx(raw_tx)?;
+
+    Ok(vec![
+        "publish".to_string(),
+        raw_tx.to_string(),
+        "--rpc-url".to_string(),
+        rpc_url.to_string(),
+    ])
+}
+
+fn validate_raw_tx(raw_tx: &str) -> Result<(), String> {
+    let hex = raw_tx
+        .strip_prefix("0x")
+        .ok_or_else(|| "raw_tx must be 0x-prefixed hex".to_string())?;
+
+    if hex.is_empty() || hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("raw_tx must be a non-empty, even-length 0x-prefixed hex string".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const RAW_TX: &str =
+        "0x02f86c0180843b9aca00850430e2340082520894d8da6bf26964af9d7eed9e03e53415d37aa9604580c0";
+
+    #[test]
+    fn build_args_minimal() {
+        let params = json!({
+            "raw_tx": RAW_TX,
+            "rpc_url": "https://eth.llamarpc.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec!["publish", RAW_TX, "--rpc-url", "https://eth.llamarpc.com"]
+        );
+    }
+
+    #[test]
+    fn build_args_has_no_secrets() {
+        let params = json!({
+            "raw_tx": RAW_TX,
+            "rpc_url": "https://rpc.example.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert!(!args.iter().any(|a| a.contains("SECRET")));
+        assert!(!args.iter().any(|a| a.contains("private-key")));
+        assert!(!args.iter().any(|a| a == "--account"));
+    }
+
+    #[test]
+    fn build_args_rejects_non_hex_raw_tx() {
+        for raw_tx in ["02f86c01", "0x", "0x02f", "0x02zz", "hello"] {
+            let params = json!({
+                "raw_tx": raw_tx,
+                "rpc_url": "https://rpc.example.com"
+            });
+            let err = build_args(&params).unwrap_err();
+            assert!(err.contains("raw_tx"), "{raw_tx}: {err}");
+        }
+    }
+
+    #[test]
+    fn build_args_rejects_missing_rpc_url() {
+        let params = json!({ "raw_tx": RAW_TX });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema_str = CastPublishTool::schema();
+        let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
+        assert_eq!(schema["title"], "cast_publish");
+        assert_eq!(schema["type"], "object");
+        assert!(schema["properties"]["raw_tx"].is_object());
+        assert!(schema["properties"]["rpc_url"].is_object());
+    }
+}
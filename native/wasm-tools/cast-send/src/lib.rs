@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde_json::{Value, json};
 use wasm_tools_common::{
-    append_signing_args, append_string_array, execute_command_tool, required_string,
-    validate_address,
+    RPC_URL_DESCRIPTION, SECRET_NAME_DESCRIPTION, USE_KEYSTORE_DESCRIPTION, append_fee_model_args,
+    append_signing_args, append_string_array, apply_context_chain, command_stdout, execute_command,
+    parse_params, required_string, run_preflight, validate_address, with_details,
 };
 
 wit_bindgen::generate!({
@@ -10,12 +13,13 @@ wit_bindgen::generate!({
 });
 
 use exports::near::agent::tool::{Guest, Request, Response};
+use near::agent::host;
 
 struct CastSendTool;
 
 impl Guest for CastSendTool {
     fn execute(req: Request) -> Response {
-        match execute_impl(&req.params) {
+        match execute_impl(&req.params, req.context.as_deref()) {
             Ok(output) => Response {
                 output: Some(output),
                 error: None,
@@ -52,11 +56,11 @@ impl Guest for CastSendTool {
                 },
                 "rpc_url": {
                     "type": "string",
-                    "description": "JSON-RPC endpoint URL"
+                    "description": RPC_URL_DESCRIPTION
                 },
                 "chain": {
                     "type": "string",
-                    "description": "Chain name or ID (e.g. 'mainnet', '1', 'sepolia')"
+                    "description": "Chain name or ID (e.g. 'mainnet', '1', 'sepolia'). Defaults to the invoke context's chain_id when omitted."
                 },
                 "gas_limit": {
                     "type": "string",
@@ -68,19 +72,44 @@ impl Guest for CastSendTool {
                 },
                 "nonce": {
                     "type": "string",
-                    "description": "Nonce override for the transaction"
+                    "description": "Nonce override for the transaction (see cast_nonce for the current value)"
+                },
+                "managed_nonce": {
+                    "type": "boolean",
+                    "description": "Have the host allocate the nonce for sender/chain and pass it as --nonce; a failed send hands it back. Cannot be combined with nonce."
+                },
+                "sender": {
+                    "type": "string",
+                    "description": "Address the transaction is sent from. Required when managed_nonce is true."
+                },
+                "current_nonce": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Current on-chain nonce for sender (e.g. from cast_nonce); resets the managed nonce tracker when provided."
+                },
+                "preflight": {
+                    "type": "boolean",
+                    "description": "First record `cast chain-id` and `cast client` for rpc_url in the result details"
+                },
+                "simulate": {
+                    "type": "boolean",
+                    "description": "Dry run: run the same transaction through `cast call` instead of sending it. Nothing is signed or broadcast and no key is needed; returns the would-be return value. Set sender to simulate as that account. Cannot be combined with managed_nonce."
+                },
+                "predict_hash": {
+                    "type": "boolean",
+                    "description": "Sign the transaction first with `cast mktx`, compute its hash, then broadcast that exact transaction with `cast publish`. The result includes `predicted_hash`, and `output` is the broadcast hash."
                 },
                 "legacy": {
                     "type": "boolean",
-                    "description": "Use legacy (pre-EIP1559) transaction format"
+                    "description": "Use legacy (pre-EIP1559) transaction format. Defaults to true on chains that only support legacy pricing (e.g. bsc, fantom); set false to override."
                 },
                 "secret_name": {
                     "type": "string",
-                    "description": "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false."
+                    "description": SECRET_NAME_DESCRIPTION
                 },
                 "use_keystore": {
                     "type": "boolean",
-                    "description": "Use Foundry keystore signing with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true)."
+                    "description": USE_KEYSTORE_DESCRIPTION
                 }
             },
             "required": ["to", "rpc_url"]
@@ -92,15 +121,196 @@ impl Guest for CastSendTool {
         "Sign and broadcast an Ethereum transaction using `cast send`. \
          Supports contract calls with function signatures and ETH transfers. \
          Signing via raw private key secret or Foundry keystore account. \
-         Credentials are injected securely and never exposed to the tool."
+         Credentials are injected securely and never exposed to the tool. \
+         Set `simulate` to check what a send would return without signing or broadcasting, \
+         or `predict_hash` to learn the transaction hash before it is broadcast."
             .to_string()
     }
 }
 
 export!(CastSendTool);
 
-fn execute_impl(params_raw: &str) -> Result<String, String> {
-    execute_command_tool(params_raw, build_args, "cast", 60_000, "cast send", "output")
+fn execute_impl(params_raw: &str, context_raw: Option<&str>) -> Result<String, String> {
+    let mut params = parse_params(params_raw)?;
+    apply_context_chain(&mut params, context_raw);
+
+    if params["simulate"].as_bool() == Some(true) {
+        return execute_simulation(&params);
+    }
+
+    // The host appends the reserved nonce to the signing command and
+    // settles it once the broadcast succeeds or fails.
+    if let Some((sender, chain)) = managed_nonce_target(&params)? {
+        host::nonce_reserve(&sender, &chain, params["current_nonce"].as_u64())
+            .map_err(|err| format!("nonce reservation failed: {err}"))?;
+    }
+
+    let details = preflight_details(&params)?;
+
+    let output = if params["predict_hash"].as_bool() == Some(true) {
+        execute_predicted_send(&params)?
+    } else {
+        let args = build_args(&params)?;
+        execute_command("cast", &args, 60_000, "cast send", "output")?
+    };
+
+    match details {
+        Some(details) => with_details(&output, details),
+        None => Ok(output),
+    }
+}
+
+/// Signs the transaction without sending it, hashes the signed bytes, then
+/// publishes those same bytes, so the hash is known before broadcast and
+/// the broadcast can't produce a different transaction.
+fn execute_predicted_send(params: &Value) -> Result<String, String> {
+    let env = BTreeMap::new();
+
+    let mktx_args = build_mktx_args(params)?;
+    let raw_tx = command_stdout("cast", &mktx_args, &env, 60_000, "cast mktx")?;
+
+    let predicted_hash =
+        command_stdout("cast", &keccak_args(&raw_tx), &env, 10_000, "cast keccak")?;
+
+    let publish_args = build_publish_args(params, &raw_tx)?;
+    let tx_hash = command_stdout("cast", &publish_args, &env, 60_000, "cast publish")?;
+
+    if !tx_hash.eq_ignore_ascii_case(&predicted_hash) {
+        return Err(format!(
+            "broadcast transaction hash {tx_hash} does not match predicted hash {predicted_hash}"
+        ));
+    }
+
+    Ok(json!({
+        "output": tx_hash,
+        "predicted_hash": predicted_hash,
+        "exit_code": 0
+    })
+    .to_string())
+}
+
+fn preflight_details(params: &Value) -> Result<Option<Value>, String> {
+    if params["preflight"].as_bool() != Some(true) {
+        return Ok(None);
+    }
+    run_preflight(required_string(params, "rpc_url")?).map(Some)
+}
+
+/// Runs the transaction through `cast call`, which executes it against the
+/// current state without signing, and marks the output `simulated`.
+fn execute_simulation(params: &Value) -> Result<String, String> {
+    if params["managed_nonce"].as_bool() == Some(true) {
+        return Err("'simulate' cannot be combined with 'managed_nonce'".to_string());
+    }
+    if params["predict_hash"].as_bool() == Some(true) {
+        return Err("'simulate' cannot be combined with 'predict_hash'".to_string());
+    }
+
+    let args = build_simulate_args(params)?;
+    let details = preflight_details(params)?;
+
+    let output = execute_command("cast", &args, 30_000, "cast call (simulate)", "output")?;
+    let mut output: Value =
+        serde_json::from_str(&output).map_err(|err| format!("invalid command output: {err}"))?;
+    output["simulated"] = json!(true);
+
+    match details {
+        Some(details) => with_details(&output.to_string(), details),
+        None => Ok(output.to_string()),
+    }
+}
+
+/// `cast call` with the same target, calldata, value, and gas settings as
+/// the send. `sender` becomes `--from`; nonce and signing options are
+/// dropped.
+fn build_simulate_args(params: &Value) -> Result<Vec<String>, String> {
+    let to = required_string(params, "to")?;
+    let rpc_url = required_string(params, "rpc_url")?;
+
+    validate_address(to)?;
+
+    let mut args: Vec<String> = vec!["call".to_string(), to.to_string()];
+
+    if let Some(sig) = params["sig"].as_str() {
+        args.push(sig.to_string());
+        append_string_array(&mut args, params, "args")?;
+    }
+
+    args.push("--rpc-url".to_string());
+    args.push(rpc_url.to_string());
+
+    if let Some(sender) = params["sender"].as_str() {
+        validate_address(sender)?;
+        args.push("--from".to_string());
+        args.push(sender.to_string());
+    }
+
+    for (key, flag) in [
+        ("value", "--value"),
+        ("chain", "--chain"),
+        ("gas_limit", "--gas-limit"),
+        ("gas_price", "--gas-price"),
+    ] {
+        if let Some(value) = params[key].as_str() {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        }
+    }
+
+    ensure_unsigned(&args)?;
+    Ok(args)
+}
+
+/// Simulations and publishes of signed transactions must never carry key
+/// material. Secret placeholders are only allowed in the RPC URL, which may
+/// embed a provider key.
+fn ensure_unsigned(args: &[String]) -> Result<(), String> {
+    const SIGNING_FLAGS: [&str; 5] = [
+        "--private-key",
+        "--account",
+        "--password",
+        "--keystore",
+        "--mnemonic",
+    ];
+
+    for (index, arg) in args.iter().enumerate() {
+        let is_rpc_url = index > 0 && args[index - 1] == "--rpc-url";
+        if SIGNING_FLAGS.contains(&arg.as_str()) || (arg.contains("{{SECRET:") && !is_rpc_url) {
+            return Err(format!(
+                "cast {} must not include signing argument '{}'",
+                args[0], arg
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the (sender, chain) key for the host nonce tracker when
+/// `managed_nonce` is set. The chain falls back to the RPC URL so sends to
+/// different networks never share a counter.
+fn managed_nonce_target(params: &Value) -> Result<Option<(String, String)>, String> {
+    if params["managed_nonce"].as_bool() != Some(true) {
+        return Ok(None);
+    }
+
+    if !params["nonce"].is_null() {
+        return Err("'nonce' cannot be combined with 'managed_nonce'".to_string());
+    }
+
+    if !params["current_nonce"].is_null() && params["current_nonce"].as_u64().is_none() {
+        return Err("'current_nonce' must be a non-negative integer".to_string());
+    }
+
+    let sender = required_string(params, "sender")?;
+    validate_address(sender)?;
+
+    let chain = match params["chain"].as_str() {
+        Some(chain) => chain,
+        None => required_string(params, "rpc_url")?,
+    };
+
+    Ok(Some((sender.to_string(), chain.to_string())))
 }
 
 fn build_args(params: &Value) -> Result<Vec<String>, String> {
@@ -144,15 +354,45 @@ fn build_args(params: &Value) -> Result<Vec<String>, String> {
         args.push(nonce.to_string());
     }
 
-    if params["legacy"].as_bool() == Some(true) {
-        args.push("--legacy".to_string());
-    }
-
+    append_fee_model_args(&mut args, params);
     append_signing_args(&mut args, params);
 
     Ok(args)
 }
 
+/// `cast mktx` takes the same arguments as `cast send` but prints the signed
+/// raw transaction instead of broadcasting it.
+fn build_mktx_args(params: &Value) -> Result<Vec<String>, String> {
+    let mut args = build_args(params)?;
+    args[0] = "mktx".to_string();
+    Ok(args)
+}
+
+/// The transaction hash is the keccak256 of the signed raw transaction.
+fn keccak_args(raw_tx: &str) -> Vec<String> {
+    vec!["keccak".to_string(), raw_tx.to_string()]
+}
+
+/// Broadcasting an already-signed transaction needs no key material.
+fn build_publish_args(params: &Value, raw_tx: &str) -> Result<Vec<String>, String> {
+    if !raw_tx.starts_with("0x") || raw_tx.len() <= 2 {
+        return Err(format!(
+            "cast mktx returned an invalid raw transaction: {raw_tx}"
+        ));
+    }
+
+    let rpc_url = required_string(params, "rpc_url")?;
+    let args = vec![
+        "publish".to_string(),
+        raw_tx.to_string(),
+        "--rpc-url".to_string(),
+        rpc_url.to_string(),
+    ];
+
+    ensure_unsigned(&args)?;
+    Ok(args)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,6 +540,122 @@ mod tests {
         assert!(build_args(&params).is_err());
     }
 
+    #[test]
+    fn managed_nonce_target_is_none_by_default() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com"
+        });
+        assert_eq!(managed_nonce_target(&params).unwrap(), None);
+    }
+
+    #[test]
+    fn managed_nonce_target_keys_by_sender_and_chain() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "chain": "sepolia",
+            "managed_nonce": true,
+            "sender": "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd"
+        });
+        assert_eq!(
+            managed_nonce_target(&params).unwrap(),
+            Some((
+                "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+                "sepolia".to_string()
+            ))
+        );
+
+        let mut without_chain = params.clone();
+        without_chain.as_object_mut().unwrap().remove("chain");
+        assert_eq!(
+            managed_nonce_target(&without_chain).unwrap().unwrap().1,
+            "https://rpc.example.com"
+        );
+    }
+
+    #[test]
+    fn managed_nonce_target_rejects_invalid_combinations() {
+        let base = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "managed_nonce": true,
+            "sender": "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd"
+        });
+
+        let mut with_nonce = base.clone();
+        with_nonce["nonce"] = json!("3");
+        assert!(managed_nonce_target(&with_nonce).is_err());
+
+        let mut bad_current = base.clone();
+        bad_current["current_nonce"] = json!(-1);
+        assert!(managed_nonce_target(&bad_current).is_err());
+
+        let mut no_sender = base.clone();
+        no_sender.as_object_mut().unwrap().remove("sender");
+        assert!(managed_nonce_target(&no_sender).is_err());
+
+        let mut bad_sender = base;
+        bad_sender["sender"] = json!("not_an_address");
+        assert!(managed_nonce_target(&bad_sender).is_err());
+    }
+
+    #[test]
+    fn context_chain_id_flows_into_args() {
+        let mut params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com"
+        });
+        apply_context_chain(&mut params, Some(r#"{"cwd":"/tmp","chain_id":8453}"#));
+
+        let args = build_args(&params).unwrap();
+        let chain_pos = args.iter().position(|arg| arg == "--chain").unwrap();
+        assert_eq!(args[chain_pos + 1], "8453");
+    }
+
+    #[test]
+    fn legacy_only_chain_defaults_to_legacy_unless_overridden() {
+        let mut params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://bsc-dataseed.bnbchain.org"
+        });
+        apply_context_chain(&mut params, Some(r#"{"chain_id":56}"#));
+        assert!(
+            build_args(&params)
+                .unwrap()
+                .contains(&"--legacy".to_string())
+        );
+
+        params["legacy"] = json!(false);
+        assert!(
+            !build_args(&params)
+                .unwrap()
+                .contains(&"--legacy".to_string())
+        );
+
+        params["chain"] = json!("mainnet");
+        params["legacy"] = Value::Null;
+        assert!(
+            !build_args(&params)
+                .unwrap()
+                .contains(&"--legacy".to_string())
+        );
+    }
+
+    #[test]
+    fn explicit_chain_overrides_context_chain_id() {
+        let mut params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "chain": "sepolia"
+        });
+        apply_context_chain(&mut params, Some(r#"{"chain_id":1}"#));
+
+        let args = build_args(&params).unwrap();
+        assert!(args.contains(&"sepolia".to_string()));
+        assert!(!args.contains(&"1".to_string()));
+    }
+
     #[test]
     fn schema_is_valid_json() {
         let schema_str = CastSendTool::schema();
@@ -308,5 +664,146 @@ mod tests {
         assert_eq!(schema["type"], "object");
         assert!(schema["properties"]["to"].is_object());
         assert!(schema["properties"]["rpc_url"].is_object());
+        assert_eq!(schema["properties"]["preflight"]["type"], "boolean");
+    }
+
+    #[test]
+    fn simulate_args_use_call_semantics_without_signing() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "transfer(address,uint256)",
+            "args": ["0xabcdefabcdefabcdefabcdefabcdefabcdefabcd", "1000"],
+            "rpc_url": "https://rpc.example.com",
+            "value": "1ether",
+            "sender": "0x000000000000000000000000000000000000dEaD",
+            "nonce": "7",
+            "legacy": true,
+            "use_keystore": false,
+            "secret_name": "DEPLOYER_KEY",
+            "simulate": true
+        });
+
+        let args = build_simulate_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "call",
+                "0x1234567890abcdef1234567890abcdef12345678",
+                "transfer(address,uint256)",
+                "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd",
+                "1000",
+                "--rpc-url",
+                "https://rpc.example.com",
+                "--from",
+                "0x000000000000000000000000000000000000dEaD",
+                "--value",
+                "1ether"
+            ]
+        );
+        assert!(!args.iter().any(|a| a.contains("SECRET")));
+        assert!(!args.contains(&"--private-key".to_string()));
+        assert!(!args.contains(&"--account".to_string()));
+    }
+
+    #[test]
+    fn simulate_allows_secret_only_in_rpc_url() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://eth-mainnet.example.com/v2/{{SECRET:RPC_KEY}}",
+            "simulate": true
+        });
+        assert!(build_simulate_args(&params).is_ok());
+
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "setKey(string)",
+            "args": ["{{SECRET:ETH_PRIVATE_KEY}}"],
+            "rpc_url": "https://rpc.example.com",
+            "simulate": true
+        });
+        assert!(build_simulate_args(&params).is_err());
+
+        let args: Vec<String> = ["call", "0x1", "--private-key", "0x01"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert!(ensure_unsigned(&args).is_err());
+    }
+
+    #[test]
+    fn simulate_rejects_managed_nonce() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "simulate": true,
+            "managed_nonce": true
+        });
+        assert_eq!(
+            execute_simulation(&params).unwrap_err(),
+            "'simulate' cannot be combined with 'managed_nonce'"
+        );
+    }
+
+    #[test]
+    fn build_args_ignores_preflight_flag() {
+        let base = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com"
+        });
+        let mut with_preflight = base.clone();
+        with_preflight["preflight"] = json!(true);
+
+        assert_eq!(
+            build_args(&with_preflight).unwrap(),
+            build_args(&base).unwrap()
+        );
+    }
+
+    #[test]
+    fn predict_hash_signs_with_mktx_then_publishes_the_signed_bytes() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "sig": "transfer(address,uint256)",
+            "args": ["0xabcdefabcdefabcdefabcdefabcdefabcdefabcd", "1000"],
+            "rpc_url": "https://rpc.example.com",
+            "nonce": "7",
+            "use_keystore": false,
+            "predict_hash": true
+        });
+
+        // Phase one signs exactly what `cast send` would have.
+        let send_args = build_args(&params).unwrap();
+        let mktx_args = build_mktx_args(&params).unwrap();
+        assert_eq!(mktx_args[0], "mktx");
+        assert_eq!(mktx_args[1..], send_args[1..]);
+        assert!(mktx_args.contains(&"{{SECRET:ETH_PRIVATE_KEY}}".to_string()));
+
+        let raw_tx = "0x02f8b20107843b9aca00";
+        assert_eq!(keccak_args(raw_tx), vec!["keccak", raw_tx]);
+
+        // Phase two broadcasts the signed bytes with no key material.
+        let publish_args = build_publish_args(&params, raw_tx).unwrap();
+        assert_eq!(
+            publish_args,
+            vec!["publish", raw_tx, "--rpc-url", "https://rpc.example.com"]
+        );
+    }
+
+    #[test]
+    fn predict_hash_rejects_bad_raw_tx_and_simulate() {
+        let params = json!({
+            "to": "0x1234567890abcdef1234567890abcdef12345678",
+            "rpc_url": "https://rpc.example.com",
+            "predict_hash": true
+        });
+        assert!(build_publish_args(&params, "").is_err());
+        assert!(build_publish_args(&params, "Error: nonce too low").is_err());
+
+        let mut simulated = params.clone();
+        simulated["simulate"] = json!(true);
+        assert_eq!(
+            execute_simulation(&simulated).unwrap_err(),
+            "'simulate' cannot be combined with 'predict_hash'"
+        );
     }
 }
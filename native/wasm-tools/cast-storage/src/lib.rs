@@ -0,0 +1,210 @@
+use serde_json::{Value, json};
+use wasm_tools_common::{
+    RPC_URL_DESCRIPTION, execute_command_tool, required_string, validate_address,
+};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct CastStorageTool;
+
+impl Guest for CastStorageTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "cast_storage",
+            "x-effects": "readonly",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Contract address (0x-prefixed hex)"
+                },
+                "slot": {
+                    "type": "string",
+                    "description": "Storage slot, decimal (e.g. '0') or 0x-prefixed hex (at most 32 bytes)"
+                },
+                "rpc_url": {
+                    "type": "string",
+                    "description": RPC_URL_DESCRIPTION
+                },
+                "block": {
+                    "type": "string",
+                    "description": "Block number or tag (e.g. 'latest', 'pending', a number)"
+                }
+            },
+            "required": ["address", "slot", "rpc_url"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Read the raw 32-byte value of a contract storage slot using `cast storage`. \
+         Read-only; no private key is needed. Useful for debugging contract state."
+            .to_string()
+    }
+}
+
+export!(CastStorageTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    execute_command_tool(
+        params_raw,
+        build_args,
+        "cast",
+        30_000,
+        "cast storage",
+        "value",
+    )
+}
+
+fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    let address = required_string(params, "address")?;
+    let slot = required_string(params, "slot")?;
+    let rpc_url = required_string(params, "rpc_url")?;
+
+    validate_address(address)?;
+    validate_slot(slot)?;
+
+    let mut args: Vec<String> = vec![
+        "storage".to_string(),
+        address.to_string(),
+        slot.to_string(),
+        "--rpc-url".to_string(),
+        rpc_url.to_string(),
+    ];
+
+    if let Some(block) = params["block"].as_str() {
+        args.push("--block".to_string());
+        args.push(block.to_string());
+    }
+
+    Ok(args)
+}
+
+/// Slots are uint256 keys: decimal digits or up to 64 hex digits after `0x`.
+fn validate_slot(slot: &str) -> Result<(), String> {
+    let valid = match slot.strip_prefix("0x") {
+        Some(hex) => {
+            !hex.is_empty() && hex.len() <= 64 && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => !slot.is_empty() && slot.chars().all(|c| c.is_ascii_digit()),
+    };
+
+    if !valid {
+        return Err(format!(
+            "invalid 'slot': {} (expected a decimal number or 0x-prefixed hex)",
+            slot
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CONTRACT: &str = "0x1234567890abcdef1234567890abcdef12345678";
+
+    #[test]
+    fn build_args_with_decimal_slot() {
+        let params = json!({
+            "address": CONTRACT,
+            "slot": "0",
+            "rpc_url": "https://eth.llamarpc.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "storage",
+                CONTRACT,
+                "0",
+                "--rpc-url",
+                "https://eth.llamarpc.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_args_with_hex_slot_and_block() {
+        let slot = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+        let params = json!({
+            "address": CONTRACT,
+            "slot": slot,
+            "rpc_url": "https://rpc.example.com",
+            "block": "19000000"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert_eq!(args[2], slot);
+        assert_eq!(&args[args.len() - 2..], ["--block", "19000000"]);
+    }
+
+    #[test]
+    fn build_args_rejects_non_numeric_slot() {
+        for slot in ["owner", "0x", "0xzz", "-1", "--rpc-url", ""] {
+            let params = json!({
+                "address": CONTRACT,
+                "slot": slot,
+                "rpc_url": "https://rpc.example.com"
+            });
+            let err = build_args(&params).unwrap_err();
+            assert!(err.starts_with("invalid 'slot'"), "{slot}: {err}");
+        }
+
+        let too_long = format!("0x{}", "1".repeat(65));
+        assert!(validate_slot(&too_long).is_err());
+    }
+
+    #[test]
+    fn build_args_rejects_invalid_address() {
+        let params = json!({
+            "address": "not_an_address",
+            "slot": "0",
+            "rpc_url": "https://rpc.example.com"
+        });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_has_no_secrets() {
+        let params = json!({
+            "address": CONTRACT,
+            "slot": "1",
+            "rpc_url": "https://rpc.example.com"
+        });
+
+        let args = build_args(&params).unwrap();
+        assert!(!args.iter().any(|a| a.contains("SECRET")));
+        assert!(!args.iter().any(|a| a.contains("private-key")));
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema_str = CastStorageTool::schema();
+        let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
+        assert_eq!(schema["title"], "cast_storage");
+        assert_eq!(schema["required"], json!(["address", "slot", "rpc_url"]));
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use wasm_tools_common::{execute_command, parse_params, typed_params, validate_address};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct CastUtilTool;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum UtilCommand {
+    Keccak,
+    Checksum,
+    Sig,
+    ToWei,
+    FromWei,
+}
+
+impl UtilCommand {
+    fn subcommand(self) -> &'static str {
+        match self {
+            Self::Keccak => "keccak",
+            Self::Checksum => "to-check-sum-address",
+            Self::Sig => "sig",
+            Self::ToWei => "to-wei",
+            Self::FromWei => "from-wei",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CastUtilParams {
+    command: UtilCommand,
+    input: String,
+    unit: Option<String>,
+}
+
+impl Guest for CastUtilTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "cast_util",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "enum": ["keccak", "checksum", "sig", "to-wei", "from-wei"],
+                    "description": "keccak: keccak256 hash of input. checksum: EIP-55 checksummed form of an address. sig: 4-byte selector of a function signature like 'transfer(address,uint256)'. to-wei / from-wei: convert an amount between unit and wei."
+                },
+                "input": {
+                    "type": "string",
+                    "description": "The data, address, function signature, or amount the command works on"
+                },
+                "unit": {
+                    "type": "string",
+                    "description": "Unit for to-wei / from-wei (e.g. ether, gwei; default: ether)"
+                }
+            },
+            "required": ["command", "input"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Offline Ethereum utilities using `cast`: keccak256 hashes, address checksums, \
+         function selectors, and wei unit conversion. No network access or keys are needed."
+            .to_string()
+    }
+}
+
+export!(CastUtilTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    let params = parse_params(params_raw)?;
+    let args = build_args(&params)?;
+    let label = format!("cast {}", args[0]);
+
+    execute_command("cast", &args, 15_000, &label, "output")
+}
+
+fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    let params: CastUtilParams = typed_params(params)?;
+
+    // Inputs are positional; a leading '-' would be parsed as a flag.
+    if params.input.is_empty() || params.input.starts_with('-') {
+        return Err("input must be non-empty and must not start with '-'".to_string());
+    }
+
+    let mut args = vec![
+        params.command.subcommand().to_string(),
+        params.input.clone(),
+    ];
+
+    match (params.command, &params.unit) {
+        (UtilCommand::ToWei | UtilCommand::FromWei, Some(unit)) => {
+            if unit.is_empty() || !unit.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err("unit must be a unit name such as 'ether' or 'gwei'".to_string());
+            }
+            args.push(unit.clone());
+        }
+        (UtilCommand::ToWei | UtilCommand::FromWei, None) => {}
+        (_, Some(_)) => return Err("unit only applies to to-wei and from-wei".to_string()),
+        (UtilCommand::Checksum, None) => validate_address(&params.input)?,
+        (UtilCommand::Keccak | UtilCommand::Sig, None) => {}
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ADDRESS: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+
+    #[test]
+    fn build_args_keccak() {
+        let args = build_args(&json!({ "command": "keccak", "input": "hello" })).unwrap();
+        assert_eq!(args, vec!["keccak", "hello"]);
+    }
+
+    #[test]
+    fn build_args_checksum_validates_address() {
+        let args = build_args(&json!({ "command": "checksum", "input": ADDRESS })).unwrap();
+        assert_eq!(args, vec!["to-check-sum-address", ADDRESS]);
+
+        assert!(build_args(&json!({ "command": "checksum", "input": "0x1234" })).is_err());
+    }
+
+    #[test]
+    fn build_args_sig() {
+        let params = json!({ "command": "sig", "input": "transfer(address,uint256)" });
+        let args = build_args(&params).unwrap();
+        assert_eq!(args, vec!["sig", "transfer(address,uint256)"]);
+    }
+
+    #[test]
+    fn build_args_unit_conversions() {
+        let args = build_args(&json!({ "command": "to-wei", "input": "1.5" })).unwrap();
+        assert_eq!(args, vec!["to-wei", "1.5"]);
+
+        let params = json!({ "command": "from-wei", "input": "1000000000", "unit": "gwei" });
+        let args = build_args(&params).unwrap();
+        assert_eq!(args, vec!["from-wei", "1000000000", "gwei"]);
+
+        let params = json!({ "command": "to-wei", "input": "1", "unit": "--rpc-url" });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_rejects_unit_outside_conversions() {
+        let params = json!({ "command": "keccak", "input": "hello", "unit": "gwei" });
+        let err = build_args(&params).unwrap_err();
+        assert!(err.contains("unit only applies"), "{err}");
+    }
+
+    #[test]
+    fn build_args_rejects_unknown_command() {
+        let err = build_args(&json!({ "command": "send", "input": "x" })).unwrap_err();
+        assert!(err.contains("command"), "{err}");
+    }
+
+    #[test]
+    fn build_args_rejects_flag_like_input() {
+        for input in ["", "--rpc-url"] {
+            let params = json!({ "command": "keccak", "input": input });
+            assert!(build_args(&params).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&CastUtilTool::schema()).expect("valid JSON");
+        assert_eq!(schema["title"], "cast_util");
+        assert_eq!(
+            schema["properties"]["command"]["enum"]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+        assert!(schema["properties"]["input"].is_object());
+    }
+}
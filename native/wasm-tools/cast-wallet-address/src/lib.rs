@@ -1,5 +1,7 @@
 use serde_json::{Value, json};
-use wasm_tools_common::{append_signing_args, execute_command_tool};
+use wasm_tools_common::{
+    SECRET_NAME_DESCRIPTION, USE_KEYSTORE_DESCRIPTION, append_signing_args, execute_command_tool,
+};
 
 wit_bindgen::generate!({
     path: "../../lemon-wasm-runtime/wit",
@@ -32,11 +34,11 @@ impl Guest for CastWalletAddressTool {
             "properties": {
                 "secret_name": {
                     "type": "string",
-                    "description": "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false."
+                    "description": SECRET_NAME_DESCRIPTION
                 },
                 "use_keystore": {
                     "type": "boolean",
-                    "description": "Use Foundry keystore account lookup with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true)."
+                    "description": USE_KEYSTORE_DESCRIPTION
                 }
             }
         })
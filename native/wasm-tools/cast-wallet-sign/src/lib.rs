@@ -1,5 +1,11 @@
 use serde_json::{Value, json};
-use wasm_tools_common::{append_signing_args, execute_command_tool, required_string};
+use tiny_keccak::{Hasher, Keccak};
+use wasm_tools_common::{
+    SECRET_NAME_DESCRIPTION, USE_KEYSTORE_DESCRIPTION, append_signing_args, execute_command_tool,
+    required_string,
+};
+
+const SIGNATURE_HEX_LEN: usize = 130;
 
 wit_bindgen::generate!({
     path: "../../lemon-wasm-runtime/wit",
@@ -32,7 +38,11 @@ impl Guest for CastWalletSignTool {
             "properties": {
                 "message": {
                     "type": "string",
-                    "description": "The message to sign"
+                    "description": "The message to sign. Required unless 'digest' is set."
+                },
+                "digest": {
+                    "type": "string",
+                    "description": "A precomputed 0x-prefixed 32-byte hex digest to sign as-is, with no hashing or prefix. Mutually exclusive with 'message' and 'typed_data'."
                 },
                 "typed_data": {
                     "type": "boolean",
@@ -40,24 +50,31 @@ impl Guest for CastWalletSignTool {
                 },
                 "no_hash": {
                     "type": "boolean",
-                    "description": "Do not hash the message before signing (use raw 32-byte input)"
+                    "description": "Do not hash the message before signing. The message must be a 0x-prefixed 32-byte hex digest."
+                },
+                "eip191": {
+                    "type": "boolean",
+                    "description": "Apply the EIP-191 personal-sign prefix (default: true). Set to false to sign keccak256(message) without any prefix; a 0x-prefixed hex message is hashed as bytes."
+                },
+                "split": {
+                    "type": "boolean",
+                    "description": "Also return the signature split into r, s (0x-prefixed 32-byte hex) and v (integer)"
                 },
                 "secret_name": {
                     "type": "string",
-                    "description": "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false."
+                    "description": SECRET_NAME_DESCRIPTION
                 },
                 "use_keystore": {
                     "type": "boolean",
-                    "description": "Use Foundry keystore signing with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true)."
+                    "description": USE_KEYSTORE_DESCRIPTION
                 }
-            },
-            "required": ["message"]
+            }
         })
         .to_string()
     }
 
     fn description() -> String {
-        "Sign a message or EIP-712 typed data using `cast wallet sign`. \
+        "Sign a message, EIP-712 typed data, or a precomputed digest using `cast wallet sign`. \
          Signing via raw private key secret or Foundry keystore account. \
          Credentials are injected securely and never exposed to the tool."
             .to_string()
@@ -67,34 +84,187 @@ impl Guest for CastWalletSignTool {
 export!(CastWalletSignTool);
 
 fn build_args(params: &Value) -> Result<Vec<String>, String> {
+    if !params["digest"].is_null() {
+        return build_digest_args(params);
+    }
+
     let message = required_string(params, "message")?;
+    let typed_data = params["typed_data"].as_bool() == Some(true);
+    let no_hash = params["no_hash"].as_bool() == Some(true);
+    let eip191 = params["eip191"].as_bool();
+
+    if no_hash && eip191 == Some(true) {
+        return Err(
+            "'no_hash' signs without the EIP-191 prefix; it conflicts with 'eip191: true'"
+                .to_string(),
+        );
+    }
+
+    let unprefixed = no_hash || eip191 == Some(false);
+
+    if typed_data && unprefixed {
+        return Err(
+            "'typed_data' cannot be combined with 'no_hash' or 'eip191: false'".to_string(),
+        );
+    }
+
+    // `no_hash` signs the message as a digest; `eip191: false` hashes it
+    // here so cast signs keccak256(message) with no prefix.
+    let message = if no_hash {
+        validate_digest("message", message)?;
+        message.to_string()
+    } else if unprefixed {
+        format!("0x{}", hex_encode(&keccak256(&message_bytes(message))))
+    } else {
+        message.to_string()
+    };
 
     let mut args: Vec<String> = vec!["wallet".to_string(), "sign".to_string()];
 
-    if params["typed_data"].as_bool() == Some(true) {
+    if typed_data {
         args.push("--data".to_string());
     }
 
-    if params["no_hash"].as_bool() == Some(true) {
+    if unprefixed {
         args.push("--no-hash".to_string());
     }
 
     append_signing_args(&mut args, params);
 
-    args.push(message.to_string());
+    args.push(message);
+
+    Ok(args)
+}
+
+/// `digest` mode: the value is signed exactly as given, as with `--no-hash`.
+fn build_digest_args(params: &Value) -> Result<Vec<String>, String> {
+    let digest = required_string(params, "digest")?;
+
+    if !params["message"].is_null() || params["typed_data"].as_bool() == Some(true) {
+        return Err("'digest' cannot be combined with 'message' or 'typed_data'".to_string());
+    }
+
+    if params["eip191"].as_bool() == Some(true) {
+        return Err(
+            "'digest' signs without the EIP-191 prefix; it conflicts with 'eip191: true'"
+                .to_string(),
+        );
+    }
+
+    validate_digest("digest", digest)?;
+
+    let mut args: Vec<String> = vec![
+        "wallet".to_string(),
+        "sign".to_string(),
+        "--no-hash".to_string(),
+    ];
+    append_signing_args(&mut args, params);
+    args.push(digest.to_string());
 
     Ok(args)
 }
 
+/// Signing without hashing only makes sense over a 32-byte digest; anything
+/// else would silently produce a signature nobody can verify.
+fn validate_digest(field: &str, value: &str) -> Result<(), String> {
+    let valid = value
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "{field} '{value}' must be a 0x-prefixed 32-byte hex digest when signing without hashing"
+        ))
+    }
+}
+
+/// The bytes cast would sign for `message`: hex-decoded when it is
+/// 0x-prefixed hex, its UTF-8 bytes otherwise.
+fn message_bytes(message: &str) -> Vec<u8> {
+    let decoded = message
+        .strip_prefix("0x")
+        .filter(|hex| hex.len().is_multiple_of(2) && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        .map(|hex| {
+            (0..hex.len())
+                .step_by(2)
+                .filter_map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).ok())
+                .collect()
+        });
+
+    decoded.unwrap_or_else(|| message.as_bytes().to_vec())
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 fn execute_impl(params_raw: &str) -> Result<String, String> {
-    execute_command_tool(
+    let mut split = false;
+
+    let output = execute_command_tool(
         params_raw,
-        build_args,
+        |params| {
+            split = params["split"].as_bool() == Some(true);
+            build_args(params)
+        },
         "cast",
         30_000,
         "cast wallet sign",
         "signature",
-    )
+    )?;
+
+    format_output(&output, split)
+}
+
+/// Adds `r`, `s` and `v` to the command output when `split` is requested,
+/// leaving the flat signature in place.
+fn format_output(output: &str, split: bool) -> Result<String, String> {
+    if !split {
+        return Ok(output.to_string());
+    }
+
+    let mut value: Value =
+        serde_json::from_str(output).map_err(|err| format!("invalid command output: {err}"))?;
+    let signature = value["signature"]
+        .as_str()
+        .ok_or_else(|| "command output has no signature".to_string())?;
+
+    let (r, s, v) = split_signature(signature)?;
+    value["r"] = json!(r);
+    value["s"] = json!(s);
+    value["v"] = json!(v);
+
+    Ok(value.to_string())
+}
+
+fn split_signature(signature: &str) -> Result<(String, String, u8), String> {
+    let hex = signature.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+
+    if hex.len() != SIGNATURE_HEX_LEN || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "unexpected signature '{signature}': expected 65 bytes of hex"
+        ));
+    }
+
+    let v = u8::from_str_radix(&hex[128..], 16)
+        .map_err(|err| format!("invalid signature v byte: {err}"))?;
+
+    Ok((
+        format!("0x{}", &hex[..64]),
+        format!("0x{}", &hex[64..128]),
+        v,
+    ))
 }
 
 #[cfg(test)]
@@ -127,11 +297,117 @@ mod tests {
         assert!(args.contains(&"--data".to_string()));
     }
 
+    const DIGEST: &str = "0x1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8";
+
     #[test]
     fn build_args_no_hash() {
-        let params = json!({ "message": "raw32bytes", "no_hash": true });
+        let params = json!({ "message": DIGEST, "no_hash": true });
+        let args = build_args(&params).unwrap();
+        assert!(args.contains(&"--no-hash".to_string()));
+    }
+
+    #[test]
+    fn build_args_no_hash_requires_32_byte_digest() {
+        for message in ["raw32bytes", "0x1234", &DIGEST[2..], &format!("{DIGEST}00")] {
+            let params = json!({ "message": message, "no_hash": true });
+            assert!(build_args(&params).is_err(), "accepted {message}");
+        }
+    }
+
+    #[test]
+    fn build_args_eip191_false_signs_keccak_of_message() {
+        // DIGEST is keccak256("hello").
+        let params = json!({ "message": "hello", "eip191": false });
         let args = build_args(&params).unwrap();
         assert!(args.contains(&"--no-hash".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some(DIGEST));
+        assert!(!args.contains(&"hello".to_string()));
+
+        let params = json!({ "message": "0x68656c6c6f", "eip191": false });
+        let args = build_args(&params).unwrap();
+        assert_eq!(args.last().map(String::as_str), Some(DIGEST));
+
+        // Unlike `no_hash`, a digest-looking message is hashed again.
+        let params = json!({ "message": DIGEST, "eip191": false });
+        let args = build_args(&params).unwrap();
+        assert_ne!(args.last().map(String::as_str), Some(DIGEST));
+    }
+
+    #[test]
+    fn build_args_eip191_true_is_the_default_prefix() {
+        let params = json!({ "message": "hello", "eip191": true });
+        let args = build_args(&params).unwrap();
+        assert!(!args.contains(&"--no-hash".to_string()));
+        assert_eq!(args, build_args(&json!({ "message": "hello" })).unwrap());
+    }
+
+    #[test]
+    fn build_args_rejects_conflicting_prefix_flags() {
+        let params = json!({ "message": DIGEST, "no_hash": true, "eip191": true });
+        assert!(build_args(&params).is_err());
+
+        let params = json!({ "message": DIGEST, "typed_data": true, "no_hash": true });
+        assert!(build_args(&params).is_err());
+
+        let params = json!({ "message": DIGEST, "typed_data": true, "eip191": false });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_digest_mode() {
+        let params = json!({ "digest": DIGEST, "use_keystore": false });
+        let args = build_args(&params).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "wallet",
+                "sign",
+                "--no-hash",
+                "--private-key",
+                "{{SECRET:ETH_PRIVATE_KEY}}",
+                DIGEST
+            ]
+        );
+
+        // Redundant but consistent flags are accepted.
+        let params = json!({ "digest": DIGEST, "no_hash": true, "eip191": false });
+        assert!(build_args(&params).is_ok());
+    }
+
+    #[test]
+    fn build_args_digest_requires_32_bytes() {
+        for digest in ["0x1234", &DIGEST[2..], &format!("{DIGEST}00"), "", "hello"] {
+            let params = json!({ "digest": digest });
+            let err = build_args(&params).unwrap_err();
+            assert!(err.starts_with("digest '"), "{digest}: {err}");
+        }
+
+        let params = json!({ "digest": 32 });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_digest_excludes_message_modes() {
+        let params = json!({ "digest": DIGEST, "message": "hello" });
+        assert!(build_args(&params).is_err());
+
+        let params = json!({ "digest": DIGEST, "typed_data": true });
+        assert!(build_args(&params).is_err());
+
+        let params = json!({ "digest": DIGEST, "eip191": true });
+        assert!(build_args(&params).is_err());
+    }
+
+    #[test]
+    fn build_args_treats_null_digest_and_message_as_absent() {
+        let params = json!({ "message": "hello", "digest": null });
+        let args = build_args(&params).unwrap();
+        assert_eq!(args.last().unwrap(), "hello");
+        assert!(!args.contains(&"--no-hash".to_string()));
+
+        let params = json!({ "digest": DIGEST, "message": null });
+        let args = build_args(&params).unwrap();
+        assert_eq!(args.last().unwrap(), DIGEST);
     }
 
     #[test]
@@ -175,12 +451,55 @@ mod tests {
         assert!(build_args(&params).is_err());
     }
 
+    const SIGNATURE: &str = "0x\
+        5f3c0e3e2b7a6b5f4d3c2b1a09f8e7d6c5b4a3928170f6e5d4c3b2a190817263\
+        4a5b6c7d8e9f00112233445566778899aabbccddeeff00112233445566778899\
+        1c";
+
+    #[test]
+    fn split_signature_parses_components() {
+        let (r, s, v) = split_signature(SIGNATURE).unwrap();
+        assert_eq!(
+            r,
+            "0x5f3c0e3e2b7a6b5f4d3c2b1a09f8e7d6c5b4a3928170f6e5d4c3b2a190817263"
+        );
+        assert_eq!(
+            s,
+            "0x4a5b6c7d8e9f00112233445566778899aabbccddeeff00112233445566778899"
+        );
+        assert_eq!(v, 28);
+    }
+
+    #[test]
+    fn split_signature_rejects_malformed_input() {
+        assert!(split_signature("0x1234").is_err());
+        assert!(split_signature(&SIGNATURE.replace('5', "z")).is_err());
+    }
+
+    #[test]
+    fn format_output_adds_components_when_split() {
+        let output = json!({ "signature": SIGNATURE, "exit_code": 0 }).to_string();
+        let value: Value = serde_json::from_str(&format_output(&output, true).unwrap()).unwrap();
+
+        assert_eq!(value["signature"], SIGNATURE);
+        assert_eq!(value["v"], 28);
+        assert!(value["r"].as_str().unwrap().starts_with("0x5f3c"));
+        assert!(value["s"].as_str().unwrap().starts_with("0x4a5b"));
+    }
+
+    #[test]
+    fn format_output_keeps_flat_signature_by_default() {
+        let output = json!({ "signature": SIGNATURE, "exit_code": 0 }).to_string();
+        assert_eq!(format_output(&output, false).unwrap(), output);
+    }
+
     #[test]
     fn schema_is_valid_json() {
         let schema_str = CastWalletSignTool::schema();
         let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
         assert_eq!(schema["title"], "cast_wallet_sign");
-        assert!(schema["required"].as_array().unwrap().contains(&json!("message")));
+        assert!(schema["required"].is_null());
+        assert!(schema["properties"]["digest"].is_object());
         assert!(schema["properties"]["use_keystore"].is_object());
     }
 }
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
 
 wit_bindgen::generate!({
@@ -11,6 +14,22 @@ pub fn parse_params(params_raw: &str) -> Result<Value, String> {
     serde_json::from_str(params_raw).map_err(|err| format!("invalid params JSON: {err}"))
 }
 
+/// Deserializes params into a tool's typed params struct. Errors name the
+/// offending field, e.g. `invalid param 'to': invalid type: integer `1`,
+/// expected a string`.
+pub fn typed_params<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(params).map_err(|err| {
+        let path = err.path().to_string();
+        let inner = err.into_inner();
+
+        if path == "." {
+            format!("invalid params: {inner}")
+        } else {
+            format!("invalid param '{path}': {inner}")
+        }
+    })
+}
+
 pub fn required_string<'a>(params: &'a Value, key: &str) -> Result<&'a str, String> {
     params[key]
         .as_str()
@@ -36,10 +55,67 @@ pub fn append_string_array(
     Ok(())
 }
 
+/// Flags that carry a credential. Raw `extra_args` may not use them: keys
+/// would bypass secret injection and land in logs, so callers go through the
+/// signing and `*_secret` params instead. Covers Foundry's multi-wallet
+/// plurals and aliases as well as the single-wallet flags.
+pub const CREDENTIAL_FLAGS: [&str; 14] = [
+    "--private-key",
+    "--private-keys",
+    "--mnemonic",
+    "--mnemonics",
+    "--mnemonic-passphrase",
+    "--mnemonic-passphrases",
+    "--keystore",
+    "--keystores",
+    "--password",
+    "--passwords",
+    "--password-file",
+    "--unsafe-password",
+    "--etherscan-api-key",
+    "--api-key",
+];
+
+/// Appends the `extra_args` string array, rejecting credential-bearing flags
+/// in both `--flag value` and `--flag=value` form.
+pub fn append_extra_args(args: &mut Vec<String>, params: &Value) -> Result<(), String> {
+    let mut extra = Vec::new();
+    append_string_array(&mut extra, params, "extra_args")?;
+
+    for arg in &extra {
+        let flag = arg.split_once('=').map_or(arg.as_str(), |(flag, _)| flag);
+        if CREDENTIAL_FLAGS.contains(&flag) {
+            return Err(format!(
+                "extra_args must not include '{flag}': pass credentials through \
+                 use_keystore/secret_name or etherscan_api_key_secret so they are \
+                 injected from secrets"
+            ));
+        }
+    }
+
+    args.extend(extra);
+    Ok(())
+}
+
+/// Schema description for `rpc_url` params. The runtime resolves secret
+/// placeholders anywhere in an exec arg and redacts the value from output,
+/// so a provider key can live in the URL without the tool seeing it.
+pub const RPC_URL_DESCRIPTION: &str = "JSON-RPC endpoint URL. If the provider embeds an API key in the URL, reference it as a secret instead of pasting it, e.g. https://eth-mainnet.g.alchemy.com/v2/{{SECRET:RPC_ALCHEMY_KEY}}";
+
 pub fn secret_placeholder(name: &str) -> String {
     format!("{{{{SECRET:{name}}}}}")
 }
 
+/// Signing secret used when `use_keystore` is false and no `secret_name` is
+/// given.
+pub const DEFAULT_SIGNING_SECRET: &str = "ETH_PRIVATE_KEY";
+
+/// Schema descriptions for the `secret_name` and `use_keystore` params that
+/// `append_signing_args` reads, shared so every signing tool documents the
+/// same defaults.
+pub const SECRET_NAME_DESCRIPTION: &str = "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false.";
+pub const USE_KEYSTORE_DESCRIPTION: &str = "Use Foundry keystore signing with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true).";
+
 pub fn append_signing_args(args: &mut Vec<String>, params: &Value) {
     if params["use_keystore"].as_bool().unwrap_or(true) {
         args.push("--account".to_string());
@@ -47,12 +123,99 @@ pub fn append_signing_args(args: &mut Vec<String>, params: &Value) {
         args.push("--password".to_string());
         args.push(secret_placeholder("KEYSTORE_PASSWORD"));
     } else {
-        let secret_name = params["secret_name"].as_str().unwrap_or("ETH_PRIVATE_KEY");
+        let secret_name = params["secret_name"]
+            .as_str()
+            .unwrap_or(DEFAULT_SIGNING_SECRET);
         args.push("--private-key".to_string());
         args.push(secret_placeholder(secret_name));
     }
 }
 
+/// Builds the child environment for Foundry commands. A `profile` param
+/// selects a `[profile.<name>]` section from foundry.toml via FOUNDRY_PROFILE.
+pub fn foundry_profile_env(params: &Value) -> Result<BTreeMap<String, String>, String> {
+    let mut env = BTreeMap::new();
+
+    if let Some(profile) = params.get("profile") {
+        let profile = profile
+            .as_str()
+            .ok_or_else(|| "'profile' must be a string".to_string())?;
+
+        if profile.is_empty()
+            || !profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "invalid Foundry profile '{profile}': use letters, digits, '_' or '-'"
+            ));
+        }
+
+        env.insert("FOUNDRY_PROFILE".to_string(), profile.to_string());
+    }
+
+    Ok(env)
+}
+
+/// Fills in `chain` from the invoke context's `chain_id` when the caller
+/// didn't pass one, so tools target the session's network by default.
+pub fn apply_context_chain(params: &mut Value, context_raw: Option<&str>) {
+    if !params["chain"].is_null() {
+        return;
+    }
+
+    let Some(context) = context_raw.and_then(|raw| serde_json::from_str::<Value>(raw).ok()) else {
+        return;
+    };
+
+    let chain = match &context["chain_id"] {
+        Value::Number(id) => id.to_string(),
+        Value::String(id) if !id.trim().is_empty() => id.trim().to_string(),
+        _ => return,
+    };
+
+    if let Some(params) = params.as_object_mut() {
+        params.insert("chain".to_string(), Value::String(chain));
+    }
+}
+
+/// Chains whose nodes only accept, or historically mishandled anything but,
+/// legacy gas pricing: (chain id, Foundry chain name).
+const LEGACY_FEE_CHAINS: [(u64, &str); 6] = [
+    (30, "rsk"),
+    (56, "bsc"),
+    (97, "bsc-testnet"),
+    (250, "fantom"),
+    (2020, "ronin"),
+    (4002, "fantom-testnet"),
+];
+
+/// Whether `chain` (an id or Foundry chain name) should default to legacy
+/// (pre-EIP-1559) transactions.
+pub fn chain_prefers_legacy(chain: &str) -> bool {
+    let chain = chain.trim();
+    match chain.parse::<u64>() {
+        Ok(id) => LEGACY_FEE_CHAINS
+            .iter()
+            .any(|(legacy_id, _)| *legacy_id == id),
+        Err(_) => LEGACY_FEE_CHAINS
+            .iter()
+            .any(|(_, name)| name.eq_ignore_ascii_case(chain)),
+    }
+}
+
+/// Adds `--legacy` when the `legacy` param is true, or when it is unset and
+/// the `chain` param names a legacy-only chain. `legacy: false` opts out.
+pub fn append_fee_model_args(args: &mut Vec<String>, params: &Value) {
+    let legacy = params["legacy"]
+        .as_bool()
+        .unwrap_or_else(|| params["chain"].as_str().is_some_and(chain_prefers_legacy));
+
+    if legacy {
+        args.push("--legacy".to_string());
+    }
+}
+
 pub fn validate_address(addr: &str) -> Result<(), String> {
     if !addr.starts_with("0x") || addr.len() != 42 {
         return Err(format!(
@@ -77,10 +240,47 @@ pub fn execute_command(
     timeout_ms: u32,
     failure_label: &str,
     success_key: &str,
+) -> Result<String, String> {
+    execute_command_with_env(
+        program,
+        args,
+        &BTreeMap::new(),
+        timeout_ms,
+        failure_label,
+        success_key,
+    )
+}
+
+pub fn execute_command_with_env(
+    program: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
+    timeout_ms: u32,
+    failure_label: &str,
+    success_key: &str,
+) -> Result<String, String> {
+    let stdout = command_stdout(program, args, env, timeout_ms, failure_label)?;
+
+    Ok(json!({
+        success_key: stdout,
+        "exit_code": 0
+    })
+    .to_string())
+}
+
+/// Runs a command through the host and returns its trimmed stdout, or an
+/// error labelled with `failure_label` on a non-zero exit.
+pub fn command_stdout(
+    program: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
+    timeout_ms: u32,
+    failure_label: &str,
 ) -> Result<String, String> {
     let args_json = serde_json::to_string(args).map_err(|err| format!("args encode: {err}"))?;
+    let env_json = serde_json::to_string(env).map_err(|err| format!("env encode: {err}"))?;
 
-    let result = host::exec_command(program, &args_json, "{}", Some(timeout_ms))
+    let result = host::exec_command(program, &args_json, &env_json, Some(timeout_ms), None)
         .map_err(|err| format!("exec failed: {err}"))?;
 
     if result.exit_code != 0 {
@@ -96,11 +296,44 @@ pub fn execute_command(
         ));
     }
 
-    Ok(json!({
-        success_key: result.stdout.trim(),
-        "exit_code": result.exit_code
-    })
-    .to_string())
+    Ok(result.stdout.trim().to_string())
+}
+
+/// `cast` invocations for the opt-in preflight that records which chain and
+/// node client a command hit, keyed by the `details` field they fill.
+pub fn preflight_args(rpc_url: &str) -> Vec<(&'static str, Vec<String>)> {
+    [("chain_id", "chain-id"), ("client_version", "client")]
+        .into_iter()
+        .map(|(key, subcommand)| {
+            let args = vec![
+                subcommand.to_string(),
+                "--rpc-url".to_string(),
+                rpc_url.to_string(),
+            ];
+            (key, args)
+        })
+        .collect()
+}
+
+/// Runs the preflight against `rpc_url` and returns the `details` object.
+pub fn run_preflight(rpc_url: &str) -> Result<Value, String> {
+    let mut details = serde_json::Map::new();
+
+    for (key, args) in preflight_args(rpc_url) {
+        let label = format!("preflight cast {}", args[0]);
+        let value = command_stdout("cast", &args, &BTreeMap::new(), 15_000, &label)?;
+        details.insert(key.to_string(), Value::String(value));
+    }
+
+    Ok(Value::Object(details))
+}
+
+/// Adds `details` to a command's JSON output.
+pub fn with_details(output: &str, details: Value) -> Result<String, String> {
+    let mut value: Value =
+        serde_json::from_str(output).map_err(|err| format!("invalid command output: {err}"))?;
+    value["details"] = details;
+    Ok(value.to_string())
 }
 
 pub fn execute_command_tool<F>(
@@ -124,6 +357,112 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct ExampleParams {
+        to: String,
+        #[serde(default)]
+        args: Vec<String>,
+    }
+
+    #[test]
+    fn typed_params_deserializes_valid_params() {
+        let params: ExampleParams =
+            typed_params(&json!({ "to": "0xabc", "args": ["1", "2"] })).unwrap();
+        assert_eq!(params.to, "0xabc");
+        assert_eq!(params.args, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn typed_params_rejects_wrong_types_with_field_path() {
+        let err = typed_params::<ExampleParams>(&json!({ "to": 1 })).unwrap_err();
+        assert!(
+            err.starts_with("invalid param 'to': invalid type: integer `1`"),
+            "{err}"
+        );
+
+        let err =
+            typed_params::<ExampleParams>(&json!({ "to": "0xabc", "args": ["1", 2] })).unwrap_err();
+        assert!(err.starts_with("invalid param 'args[1]'"), "{err}");
+    }
+
+    #[test]
+    fn typed_params_rejects_missing_and_unknown_fields() {
+        let err = typed_params::<ExampleParams>(&json!({})).unwrap_err();
+        assert_eq!(err, "invalid params: missing field `to`");
+
+        let err =
+            typed_params::<ExampleParams>(&json!({ "to": "0xabc", "extra": true })).unwrap_err();
+        assert!(err.contains("unknown field `extra`"), "{err}");
+    }
+
+    #[test]
+    fn legacy_chains_match_by_id_or_name() {
+        for chain in ["56", "bsc", "BSC", "250", " fantom ", "fantom-testnet"] {
+            assert!(chain_prefers_legacy(chain), "{chain}");
+        }
+        for chain in ["1", "mainnet", "8453", "base", "bscscan", ""] {
+            assert!(!chain_prefers_legacy(chain), "{chain}");
+        }
+    }
+
+    #[test]
+    fn fee_model_args_default_from_chain_and_honor_override() {
+        let fee_args = |params: Value| {
+            let mut args = Vec::new();
+            append_fee_model_args(&mut args, &params);
+            args
+        };
+
+        assert_eq!(fee_args(json!({ "chain": "bsc" })), vec!["--legacy"]);
+        assert!(fee_args(json!({ "chain": "bsc", "legacy": false })).is_empty());
+        assert!(fee_args(json!({ "chain": "mainnet" })).is_empty());
+        assert_eq!(
+            fee_args(json!({ "chain": "mainnet", "legacy": true })),
+            vec!["--legacy"]
+        );
+        assert!(fee_args(json!({})).is_empty());
+    }
+
+    #[test]
+    fn preflight_args_query_chain_id_and_client() {
+        let args = preflight_args("https://rpc.example.com");
+        assert_eq!(
+            args,
+            vec![
+                (
+                    "chain_id",
+                    vec![
+                        "chain-id".to_string(),
+                        "--rpc-url".to_string(),
+                        "https://rpc.example.com".to_string()
+                    ]
+                ),
+                (
+                    "client_version",
+                    vec![
+                        "client".to_string(),
+                        "--rpc-url".to_string(),
+                        "https://rpc.example.com".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_details_adds_details_to_output() {
+        let output = json!({ "output": "0x01", "exit_code": 0 }).to_string();
+        let details = json!({ "chain_id": "1", "client_version": "Geth/v1.14.0" });
+
+        let value: Value = serde_json::from_str(&with_details(&output, details).unwrap()).unwrap();
+        assert_eq!(value["output"], "0x01");
+        assert_eq!(value["details"]["chain_id"], "1");
+        assert_eq!(value["details"]["client_version"], "Geth/v1.14.0");
+
+        assert!(with_details("not json", json!({})).is_err());
+    }
+
     #[test]
     fn validate_address_accepts_valid_values() {
         assert!(validate_address("0x1234567890abcdef1234567890abcdef12345678").is_ok());
@@ -137,6 +476,27 @@ mod tests {
         assert!(validate_address("0xGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG").is_err());
     }
 
+    #[test]
+    fn append_extra_args_rejects_credential_flags() {
+        for flag in CREDENTIAL_FLAGS {
+            for extra in [json!([flag, "0xabc"]), json!([format!("{flag}=0xabc")])] {
+                let mut args = Vec::new();
+                let err =
+                    append_extra_args(&mut args, &json!({ "extra_args": extra })).expect_err(flag);
+                assert!(err.contains(&format!("'{flag}'")), "{err}");
+                assert!(args.is_empty());
+            }
+        }
+
+        let mut args = vec!["create".to_string()];
+        append_extra_args(
+            &mut args,
+            &json!({ "extra_args": ["--via-ir", "--password-less"] }),
+        )
+        .unwrap();
+        assert_eq!(args, vec!["create", "--via-ir", "--password-less"]);
+    }
+
     #[test]
     fn append_signing_args_defaults_to_keystore() {
         let mut args = vec!["cast".to_string()];
@@ -154,6 +514,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn signing_descriptions_match_append_signing_args_defaults() {
+        assert!(SECRET_NAME_DESCRIPTION.contains(&format!("default: {DEFAULT_SIGNING_SECRET}")));
+        assert!(USE_KEYSTORE_DESCRIPTION.contains("default: true"));
+
+        let mut args = Vec::new();
+        append_signing_args(&mut args, &json!({ "use_keystore": false }));
+        assert_eq!(args[1], secret_placeholder(DEFAULT_SIGNING_SECRET));
+    }
+
     #[test]
     fn append_signing_args_supports_private_key_mode() {
         let mut args = vec!["cast".to_string()];
@@ -171,6 +541,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn foundry_profile_env_sets_profile_when_provided() {
+        let env = foundry_profile_env(&json!({ "profile": "ci" })).unwrap();
+        assert_eq!(env.get("FOUNDRY_PROFILE").map(String::as_str), Some("ci"));
+
+        assert!(foundry_profile_env(&json!({})).unwrap().is_empty());
+    }
+
+    #[test]
+    fn foundry_profile_env_rejects_invalid_profiles() {
+        assert!(foundry_profile_env(&json!({ "profile": "" })).is_err());
+        assert!(foundry_profile_env(&json!({ "profile": "ci; rm -rf" })).is_err());
+        assert!(foundry_profile_env(&json!({ "profile": 1 })).is_err());
+    }
+
+    #[test]
+    fn apply_context_chain_fills_missing_chain() {
+        let mut params = json!({});
+        apply_context_chain(&mut params, Some(r#"{"chain_id":8453}"#));
+        assert_eq!(params["chain"], "8453");
+
+        let mut params = json!({});
+        apply_context_chain(&mut params, Some(r#"{"chain_id":"sepolia"}"#));
+        assert_eq!(params["chain"], "sepolia");
+    }
+
+    #[test]
+    fn apply_context_chain_keeps_explicit_chain_and_ignores_bad_context() {
+        let mut params = json!({ "chain": "mainnet" });
+        apply_context_chain(&mut params, Some(r#"{"chain_id":10}"#));
+        assert_eq!(params["chain"], "mainnet");
+
+        for context in [
+            None,
+            Some("not json"),
+            Some("{}"),
+            Some(r#"{"chain_id":""}"#),
+        ] {
+            let mut params = json!({});
+            apply_context_chain(&mut params, context);
+            assert!(params["chain"].is_null(), "context {context:?}");
+        }
+    }
+
     #[test]
     fn append_string_array_validates_elements() {
         let mut args = Vec::new();
@@ -0,0 +1,281 @@
+use serde_json::{Value, json};
+use tiny_keccak::{Hasher, Keccak};
+use wasm_tools_common::{parse_params, required_string, validate_address};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+
+struct Create2AddressTool;
+
+impl Guest for Create2AddressTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "create2_address",
+            "x-effects": "pure",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "deployer": {
+                    "type": "string",
+                    "description": "Address of the deploying contract or factory (0x-prefixed hex)"
+                },
+                "salt": {
+                    "type": "string",
+                    "description": "32-byte salt (0x-prefixed, 64 hex chars)"
+                },
+                "init_code_hash": {
+                    "type": "string",
+                    "description": "keccak256 of the contract init code (0x-prefixed, 64 hex chars)"
+                }
+            },
+            "required": ["deployer", "salt", "init_code_hash"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Predict the address of a contract deployed with CREATE2 from the deployer address, \
+         salt, and init code hash. Runs fully offline and returns both the EIP-55 checksummed \
+         and lowercase forms of the address."
+            .to_string()
+    }
+}
+
+export!(Create2AddressTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    let params = parse_params(params_raw)?;
+    let address = compute_address(&params)?;
+
+    Ok(json!({
+        "address": to_checksum_address(&address),
+        "address_lowercase": format!("0x{}", hex_encode(&address)),
+    })
+    .to_string())
+}
+
+fn compute_address(params: &Value) -> Result<[u8; 20], String> {
+    let deployer = required_string(params, "deployer")?;
+    let salt = required_string(params, "salt")?;
+    let init_code_hash = required_string(params, "init_code_hash")?;
+
+    validate_address(deployer)?;
+    let deployer = decode_hex(&deployer[2..])?;
+    let salt = parse_bytes32("salt", salt)?;
+    let init_code_hash = parse_bytes32("init_code_hash", init_code_hash)?;
+
+    let mut preimage = Vec::with_capacity(1 + 20 + 32 + 32);
+    preimage.push(0xff);
+    preimage.extend_from_slice(&deployer);
+    preimage.extend_from_slice(&salt);
+    preimage.extend_from_slice(&init_code_hash);
+
+    let hash = keccak256(&preimage);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+
+    Ok(address)
+}
+
+fn parse_bytes32(key: &str, value: &str) -> Result<[u8; 32], String> {
+    let hex = value
+        .strip_prefix("0x")
+        .filter(|hex| hex.len() == 64)
+        .ok_or_else(|| format!("'{key}' must be a 0x-prefixed 32-byte hex string"))?;
+
+    let bytes = decode_hex(hex).map_err(|err| format!("'{key}': {err}"))?;
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+
+    Ok(out)
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    // Checked up front so the byte-offset slices below are always on char
+    // boundaries.
+    if let Some(invalid) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex character '{invalid}'"));
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err("hex string has odd length".to_string());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| {
+            u8::from_str_radix(&hex[idx..idx + 2], 16)
+                .map_err(|_| format!("invalid hex characters '{}'", &hex[idx..idx + 2]))
+        })
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/// EIP-55 mixed-case checksum encoding.
+fn to_checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex_encode(address);
+    let hash = keccak256(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(idx, ch)| {
+            let byte = hash[idx / 2];
+            let nibble = if idx % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            if ch.is_ascii_alphabetic() && nibble >= 8 {
+                ch.to_ascii_uppercase()
+            } else {
+                ch
+            }
+        })
+        .collect();
+
+    format!("0x{checksummed}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // keccak256(0x00), the init code used by the EIP-1014 examples.
+    const INIT_CODE_HASH_00: &str =
+        "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a";
+    const ZERO_SALT: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+    #[test]
+    fn computes_eip1014_vector_zero_deployer() {
+        let params = json!({
+            "deployer": "0x0000000000000000000000000000000000000000",
+            "salt": ZERO_SALT,
+            "init_code_hash": INIT_CODE_HASH_00
+        });
+
+        let address = compute_address(&params).unwrap();
+        assert_eq!(
+            to_checksum_address(&address),
+            "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"
+        );
+    }
+
+    #[test]
+    fn computes_eip1014_vector_with_salt() {
+        let params = json!({
+            "deployer": "0xdeadbeef00000000000000000000000000000000",
+            "salt": "0x000000000000000000000000feed000000000000000000000000000000000000",
+            "init_code_hash": INIT_CODE_HASH_00
+        });
+
+        let address = compute_address(&params).unwrap();
+        assert_eq!(
+            to_checksum_address(&address),
+            "0xD04116cDd17beBE565EB2422F2497E06cC1C9833"
+        );
+    }
+
+    #[test]
+    fn execute_returns_checksummed_and_lowercase() {
+        let output = execute_impl(
+            &json!({
+                "deployer": "0xdeadbeef00000000000000000000000000000000",
+                "salt": ZERO_SALT,
+                "init_code_hash": INIT_CODE_HASH_00
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            output["address"],
+            "0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3"
+        );
+        assert_eq!(
+            output["address_lowercase"],
+            "0xb928f69bb1d91cd65274e3c79d8986362984fda3"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_deployer() {
+        let params = json!({
+            "deployer": "0x1234",
+            "salt": ZERO_SALT,
+            "init_code_hash": INIT_CODE_HASH_00
+        });
+        assert!(compute_address(&params).is_err());
+    }
+
+    #[test]
+    fn rejects_short_salt() {
+        let params = json!({
+            "deployer": "0x0000000000000000000000000000000000000000",
+            "salt": "0x00",
+            "init_code_hash": INIT_CODE_HASH_00
+        });
+        let err = compute_address(&params).unwrap_err();
+        assert!(err.contains("'salt'"));
+    }
+
+    #[test]
+    fn rejects_non_hex_init_code_hash() {
+        let params = json!({
+            "deployer": "0x0000000000000000000000000000000000000000",
+            "salt": ZERO_SALT,
+            "init_code_hash": "0xzz36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a"
+        });
+        let err = compute_address(&params).unwrap_err();
+        assert!(err.contains("'init_code_hash'"));
+    }
+
+    #[test]
+    fn rejects_non_ascii_salt_without_panicking() {
+        let params = json!({
+            "deployer": "0x0000000000000000000000000000000000000000",
+            "salt": format!("0xa\u{e9}{}", "a".repeat(61)),
+            "init_code_hash": INIT_CODE_HASH_00
+        });
+        let err = compute_address(&params).unwrap_err();
+        assert!(err.contains("'salt'") && err.contains('\u{e9}'), "{err}");
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema_str = Create2AddressTool::schema();
+        let schema: serde_json::Value = serde_json::from_str(&schema_str).expect("valid JSON");
+        assert_eq!(schema["title"], "create2_address");
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("salt"))
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use serde_json::{Value, json};
 use wasm_tools_common::{
-    append_signing_args, append_string_array, execute_command_tool, required_string,
+    RPC_URL_DESCRIPTION, SECRET_NAME_DESCRIPTION, USE_KEYSTORE_DESCRIPTION, append_extra_args,
+    append_fee_model_args, append_signing_args, append_string_array, apply_context_chain,
+    execute_command_with_env, foundry_profile_env, parse_params, required_string,
     secret_placeholder,
 };
 
@@ -15,7 +17,7 @@ struct ForgeCreateTool;
 
 impl Guest for ForgeCreateTool {
     fn execute(req: Request) -> Response {
-        match execute_impl(&req.params) {
+        match execute_impl(&req.params, req.context.as_deref()) {
             Ok(output) => Response {
                 output: Some(output),
                 error: None,
@@ -48,11 +50,15 @@ impl Guest for ForgeCreateTool {
                 },
                 "rpc_url": {
                     "type": "string",
-                    "description": "JSON-RPC endpoint URL"
+                    "description": RPC_URL_DESCRIPTION
                 },
                 "chain": {
                     "type": "string",
-                    "description": "Chain name or ID"
+                    "description": "Chain name or ID. Defaults to the invoke context's chain_id when omitted."
+                },
+                "legacy": {
+                    "type": "boolean",
+                    "description": "Use legacy (pre-EIP1559) transaction format. Defaults to true on chains that only support legacy pricing (e.g. bsc, fantom); set false to override."
                 },
                 "verify": {
                     "type": "boolean",
@@ -62,18 +68,22 @@ impl Guest for ForgeCreateTool {
                     "type": "string",
                     "description": "Secret name for the Etherscan API key (used with --verify)"
                 },
+                "profile": {
+                    "type": "string",
+                    "description": "Foundry profile to use (sets FOUNDRY_PROFILE, e.g. 'ci')"
+                },
                 "extra_args": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Additional raw flags to pass to forge create"
+                    "description": "Additional raw flags to pass to forge create. Credential flags (--private-key, --mnemonic, --keystore, --password, --etherscan-api-key) are rejected; use the signing and secret params instead."
                 },
                 "secret_name": {
                     "type": "string",
-                    "description": "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false."
+                    "description": SECRET_NAME_DESCRIPTION
                 },
                 "use_keystore": {
                     "type": "boolean",
-                    "description": "Use Foundry keystore signing with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true)."
+                    "description": USE_KEYSTORE_DESCRIPTION
                 }
             },
             "required": ["contract", "rpc_url"]
@@ -125,27 +135,28 @@ fn build_args(params: &Value) -> Result<Vec<String>, String> {
         }
     }
 
+    append_fee_model_args(&mut args, params);
     append_signing_args(&mut args, params);
-    append_string_array(&mut args, params, "extra_args")?;
+    append_extra_args(&mut args, params)?;
 
     Ok(args)
 }
 
-fn execute_impl(params_raw: &str) -> Result<String, String> {
-    execute_command_tool(
-        params_raw,
-        build_args,
-        "forge",
-        120_000,
-        "forge create",
-        "output",
-    )
+fn execute_impl(params_raw: &str, context_raw: Option<&str>) -> Result<String, String> {
+    let mut params = parse_params(params_raw)?;
+    apply_context_chain(&mut params, context_raw);
+
+    let args = build_args(&params)?;
+    let env = foundry_profile_env(&params)?;
+
+    execute_command_with_env("forge", &args, &env, 120_000, "forge create", "output")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use wasm_tools_common::CREDENTIAL_FLAGS;
 
     #[test]
     fn build_args_minimal() {
@@ -209,6 +220,27 @@ mod tests {
         assert!(args.contains(&"--chain".to_string()));
     }
 
+    #[test]
+    fn build_args_defaults_to_legacy_on_legacy_only_chain() {
+        let mut params = json!({
+            "contract": "src/Counter.sol:Counter",
+            "rpc_url": "https://rpc.ftm.tools",
+            "chain": "fantom"
+        });
+        assert!(
+            build_args(&params)
+                .unwrap()
+                .contains(&"--legacy".to_string())
+        );
+
+        params["legacy"] = json!(false);
+        assert!(
+            !build_args(&params)
+                .unwrap()
+                .contains(&"--legacy".to_string())
+        );
+    }
+
     #[test]
     fn build_args_with_extra_args() {
         let params = json!({
@@ -221,6 +253,20 @@ mod tests {
         assert!(args.contains(&"--via-ir".to_string()));
     }
 
+    #[test]
+    fn build_args_rejects_credential_flags_in_extra_args() {
+        for flag in CREDENTIAL_FLAGS {
+            let params = json!({
+                "contract": "src/Counter.sol:Counter",
+                "rpc_url": "https://rpc.example.com",
+                "extra_args": [flag, "0x1234"]
+            });
+
+            let err = build_args(&params).unwrap_err();
+            assert!(err.contains(flag), "{err}");
+        }
+    }
+
     #[test]
     fn build_args_uses_keystore_by_default() {
         let params = json!({
@@ -263,6 +309,20 @@ mod tests {
         assert!(build_args(&params).is_err());
     }
 
+    #[test]
+    fn profile_param_sets_foundry_profile_env() {
+        let params = json!({
+            "contract": "src/Counter.sol:Counter",
+            "rpc_url": "https://rpc.example.com",
+            "profile": "ci"
+        });
+
+        let env = foundry_profile_env(&params).unwrap();
+        assert_eq!(env.get("FOUNDRY_PROFILE").map(String::as_str), Some("ci"));
+        // The profile is passed via the environment, never as a CLI flag.
+        assert!(!build_args(&params).unwrap().contains(&"ci".to_string()));
+    }
+
     #[test]
     fn schema_is_valid_json() {
         let schema_str = ForgeCreateTool::schema();
@@ -1,7 +1,8 @@
 use serde_json::{Value, json};
 use wasm_tools_common::{
-    append_signing_args, append_string_array, execute_command_tool, required_string,
-    secret_placeholder,
+    RPC_URL_DESCRIPTION, SECRET_NAME_DESCRIPTION, USE_KEYSTORE_DESCRIPTION, append_extra_args,
+    append_signing_args, append_string_array, apply_context_chain, command_stdout,
+    foundry_profile_env, parse_params, required_string, secret_placeholder,
 };
 
 wit_bindgen::generate!({
@@ -10,12 +11,13 @@ wit_bindgen::generate!({
 });
 
 use exports::near::agent::tool::{Guest, Request, Response};
+use near::agent::host;
 
 struct ForgeScriptTool;
 
 impl Guest for ForgeScriptTool {
     fn execute(req: Request) -> Response {
-        match execute_impl(&req.params) {
+        match execute_impl(&req.params, req.context.as_deref()) {
             Ok(output) => Response {
                 output: Some(output),
                 error: None,
@@ -48,16 +50,20 @@ impl Guest for ForgeScriptTool {
                 },
                 "rpc_url": {
                     "type": "string",
-                    "description": "JSON-RPC endpoint URL"
+                    "description": RPC_URL_DESCRIPTION
                 },
                 "chain": {
                     "type": "string",
-                    "description": "Chain name or ID"
+                    "description": "Chain name or ID. Defaults to the invoke context's chain_id when omitted."
                 },
                 "broadcast": {
                     "type": "boolean",
                     "description": "Broadcast transactions on-chain (default: false, dry-run)"
                 },
+                "parse_broadcast": {
+                    "type": "boolean",
+                    "description": "With broadcast, read the run's broadcast/<script>/<chain>/<function>-latest.json and list deployed contracts in `deployments` (default: false). Requires the workspace capability."
+                },
                 "verify": {
                     "type": "boolean",
                     "description": "Verify contracts on Etherscan after deployment"
@@ -66,18 +72,22 @@ impl Guest for ForgeScriptTool {
                     "type": "string",
                     "description": "Secret name for the Etherscan API key (used with --verify)"
                 },
+                "profile": {
+                    "type": "string",
+                    "description": "Foundry profile to use (sets FOUNDRY_PROFILE, e.g. 'ci')"
+                },
                 "extra_args": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Additional raw flags to pass to forge script"
+                    "description": "Additional raw flags to pass to forge script. Credential flags (--private-key, --mnemonic, --keystore, --password, --etherscan-api-key) are rejected; use the signing and secret params instead."
                 },
                 "secret_name": {
                     "type": "string",
-                    "description": "Secret name for the signing private key (default: ETH_PRIVATE_KEY). Used only when use_keystore is false."
+                    "description": SECRET_NAME_DESCRIPTION
                 },
                 "use_keystore": {
                     "type": "boolean",
-                    "description": "Use Foundry keystore signing with KEYSTORE_NAME and KEYSTORE_PASSWORD secrets (default: true)."
+                    "description": USE_KEYSTORE_DESCRIPTION
                 }
             },
             "required": ["script", "rpc_url"]
@@ -88,7 +98,9 @@ impl Guest for ForgeScriptTool {
     fn description() -> String {
         "Run a Forge deployment/interaction script using `forge script`. \
          Supports dry-run and broadcast modes. Signing via raw private key secret or \
-         Foundry keystore account. Credentials are injected securely and never exposed to the tool."
+         Foundry keystore account. Credentials are injected securely and never exposed to the tool. \
+         Values returned by the script function are listed in `returns` as typed entries. \
+         With `parse_broadcast`, contracts created by the broadcast are listed in `deployments`."
             .to_string()
     }
 }
@@ -130,26 +142,290 @@ fn build_args(params: &Value) -> Result<Vec<String>, String> {
     }
 
     append_signing_args(&mut args, params);
-    append_string_array(&mut args, params, "extra_args")?;
+    append_extra_args(&mut args, params)?;
 
     Ok(args)
 }
 
-fn execute_impl(params_raw: &str) -> Result<String, String> {
-    execute_command_tool(
-        params_raw,
-        build_args,
-        "forge",
-        120_000,
-        "forge script",
-        "output",
-    )
+fn execute_impl(params_raw: &str, context_raw: Option<&str>) -> Result<String, String> {
+    let mut params = parse_params(params_raw)?;
+    apply_context_chain(&mut params, context_raw);
+
+    let args = build_args(&params)?;
+    let env = foundry_profile_env(&params)?;
+
+    let parse_broadcast = params["parse_broadcast"].as_bool() == Some(true);
+    if parse_broadcast {
+        if params["broadcast"].as_bool() != Some(true) {
+            return Err("'parse_broadcast' requires 'broadcast'".to_string());
+        }
+        // Fail before broadcasting rather than after the transactions land.
+        if !workspace_readable() {
+            return Err(
+                "'parse_broadcast' needs the workspace capability to read broadcast artifacts"
+                    .to_string(),
+            );
+        }
+    }
+
+    let output = command_stdout("forge", &args, &env, 120_000, "forge script")?;
+    let returns = parse_returns(&output);
+
+    let mut result = json!({ "output": output, "exit_code": 0 });
+    if !returns.is_empty() {
+        result["returns"] = Value::Array(returns);
+    }
+    if parse_broadcast {
+        result["deployments"] = Value::Array(read_deployments(&params)?);
+    }
+    Ok(result.to_string())
+}
+
+fn workspace_readable() -> bool {
+    serde_json::from_str::<Value>(&host::capabilities_summary())
+        .is_ok_and(|summary| summary["workspace_read"].as_bool() == Some(true))
+}
+
+/// Locates and parses the run file forge wrote for this broadcast. Without a
+/// numeric chain the chain directory is found by listing, and must be unique.
+fn read_deployments(params: &Value) -> Result<Vec<Value>, String> {
+    let (dir, file_name) = broadcast_location(params)?;
+
+    let path = match params["chain"].as_str() {
+        Some(chain) if chain.parse::<u64>().is_ok() => format!("{}/{}/{}", dir, chain, file_name),
+        _ => {
+            let mut found = host::workspace_list(&dir, &format!("*/{}", file_name));
+            match found.len() {
+                1 => found.remove(0),
+                0 => return Err(format!("no {} found under {}", file_name, dir)),
+                _ => {
+                    return Err(format!(
+                        "found {} for several chains under {}; pass a numeric 'chain'",
+                        file_name, dir
+                    ));
+                }
+            }
+        }
+    };
+
+    let raw = host::workspace_read(&path, None)
+        .ok_or_else(|| format!("could not read broadcast artifact {}", path))?;
+    parse_deployments(&raw).map_err(|err| format!("{}: {}", path, err))
+}
+
+/// `script/Deploy.s.sol:Deploy` with `--sig "deploy(uint256)"` broadcasts to
+/// `broadcast/Deploy.s.sol/<chain id>/deploy-latest.json`.
+fn broadcast_location(params: &Value) -> Result<(String, String), String> {
+    let script = required_string(params, "script")?;
+    let script_path = script.split(':').next().unwrap_or(script);
+    let script_file = script_path.rsplit('/').next().unwrap_or(script_path);
+
+    let function = params["sig"]
+        .as_str()
+        .map(|sig| sig.split('(').next().unwrap_or(sig))
+        .unwrap_or("run");
+
+    let safe = |part: &str| {
+        !part.is_empty() && part != ".." && !part.contains('/') && !part.contains('\\')
+    };
+    if !safe(script_file) || !safe(function) {
+        return Err(format!(
+            "cannot derive a broadcast artifact path from '{}'",
+            script
+        ));
+    }
+
+    Ok((
+        format!("broadcast/{}", script_file),
+        format!("{}-latest.json", function),
+    ))
+}
+
+/// Extracts `{contract_name, address, tx_hash}` for every CREATE/CREATE2
+/// transaction in a broadcast run file, including contracts deployed by
+/// factories (`additionalContracts`), which share their parent's hash.
+fn parse_deployments(raw: &str) -> Result<Vec<Value>, String> {
+    let artifact: Value =
+        serde_json::from_str(raw).map_err(|err| format!("invalid broadcast artifact: {}", err))?;
+    let transactions = artifact["transactions"]
+        .as_array()
+        .ok_or_else(|| "broadcast artifact has no 'transactions' array".to_string())?;
+
+    let is_create = |entry: &Value| {
+        matches!(
+            entry["transactionType"].as_str(),
+            Some("CREATE" | "CREATE2")
+        )
+    };
+
+    let mut deployments = Vec::new();
+    for tx in transactions {
+        if is_create(tx) {
+            deployments.push(json!({
+                "contract_name": tx["contractName"],
+                "address": tx["contractAddress"],
+                "tx_hash": tx["hash"],
+            }));
+        }
+
+        for extra in tx["additionalContracts"].as_array().into_iter().flatten() {
+            if is_create(extra) {
+                deployments.push(json!({
+                    "contract_name": extra["contractName"],
+                    "address": extra["address"],
+                    "tx_hash": tx["hash"],
+                }));
+            }
+        }
+    }
+
+    Ok(deployments)
+}
+
+/// Parses the `== Return ==` section of `forge script` output, where each
+/// line is `<name or index>: <type> <value>` in declaration order, into
+/// `{index, name, type, value}` entries.
+fn parse_returns(output: &str) -> Vec<Value> {
+    let Some((_, section)) = output.split_once("== Return ==") else {
+        return Vec::new();
+    };
+
+    section
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty() && !line.starts_with("=="))
+        .filter_map(|line| line.split_once(": "))
+        .enumerate()
+        .map(|(index, (label, rest))| {
+            let (ty, raw) = split_type(rest);
+            let name = match label.parse::<usize>() {
+                Ok(_) => Value::Null,
+                Err(_) => Value::String(label.to_string()),
+            };
+
+            json!({
+                "index": index,
+                "name": name,
+                "type": ty,
+                "value": parse_value(ty, raw),
+            })
+        })
+        .collect()
+}
+
+/// Splits `<type> <value>`. Internal types for structs, contracts, and
+/// enums are two words, e.g. `struct Config.Pool[] [(1, 2)]`.
+fn split_type(rest: &str) -> (&str, &str) {
+    let type_words = if ["struct ", "contract ", "enum "]
+        .iter()
+        .any(|prefix| rest.starts_with(prefix))
+    {
+        2
+    } else {
+        1
+    };
+
+    let split_at = rest
+        .match_indices(' ')
+        .nth(type_words - 1)
+        .map(|(at, _)| at)
+        .unwrap_or(rest.len());
+
+    (&rest[..split_at], rest[split_at..].trim())
+}
+
+/// Best-effort conversion of a formatted Solidity value. Arrays and tuples
+/// become JSON arrays; numbers stay strings to keep full precision.
+fn parse_value(ty: &str, raw: &str) -> Value {
+    if let Some(element_ty) = array_element_type(ty)
+        && let Some(inner) = raw.strip_prefix('[').and_then(|raw| raw.strip_suffix(']'))
+    {
+        return Value::Array(
+            split_top_level(inner)
+                .into_iter()
+                .map(|item| parse_value(element_ty, item))
+                .collect(),
+        );
+    }
+
+    if let Some(inner) = raw.strip_prefix('(').and_then(|raw| raw.strip_suffix(')')) {
+        return Value::Array(
+            split_top_level(inner)
+                .into_iter()
+                .map(|item| parse_value("", item))
+                .collect(),
+        );
+    }
+
+    match ty {
+        "bool" => match raw {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(raw.to_string()),
+        },
+        "string" => Value::String(
+            raw.strip_prefix('"')
+                .and_then(|raw| raw.strip_suffix('"'))
+                .unwrap_or(raw)
+                .to_string(),
+        ),
+        // Untyped tuple members that are themselves arrays.
+        "" if raw.starts_with('[') && raw.ends_with(']') => Value::Array(
+            split_top_level(&raw[1..raw.len() - 1])
+                .into_iter()
+                .map(|item| parse_value("", item))
+                .collect(),
+        ),
+        // Drop forge's scientific-notation hint, e.g. `1000000 [1e6]`.
+        ty if ty.starts_with("uint") || ty.starts_with("int") => Value::String(
+            raw.split_once(" [")
+                .map(|(number, _)| number)
+                .unwrap_or(raw)
+                .to_string(),
+        ),
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+/// `uint256[]` -> `uint256`, `address[2][]` -> `address[2]`.
+fn array_element_type(ty: &str) -> Option<&str> {
+    let ty = ty.strip_suffix(']')?;
+    ty.rfind('[').map(|open| &ty[..open])
+}
+
+/// Splits on commas that aren't nested in brackets, parens, or quotes.
+fn split_top_level(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (at, ch) in inner.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '[' | '(' if !in_string => depth += 1,
+            ']' | ')' if !in_string => depth = depth.saturating_sub(1),
+            ',' if !in_string && depth == 0 => {
+                items.push(inner[start..at].trim());
+                start = at + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let last = inner[start..].trim();
+    if !last.is_empty() || !items.is_empty() {
+        items.push(last);
+    }
+    items
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use wasm_tools_common::CREDENTIAL_FLAGS;
 
     #[test]
     fn build_args_minimal() {
@@ -208,6 +484,20 @@ mod tests {
         assert!(args.contains(&"--optimize".to_string()));
     }
 
+    #[test]
+    fn build_args_rejects_credential_flags_in_extra_args() {
+        for flag in CREDENTIAL_FLAGS {
+            let params = json!({
+                "script": "script/Deploy.s.sol",
+                "rpc_url": "https://rpc.example.com",
+                "extra_args": [format!("{flag}=0x1234")]
+            });
+
+            let err = build_args(&params).unwrap_err();
+            assert!(err.contains(flag), "{err}");
+        }
+    }
+
     #[test]
     fn build_args_verify_without_etherscan_key() {
         let params = json!({
@@ -264,6 +554,182 @@ mod tests {
         assert!(build_args(&params).is_err());
     }
 
+    #[test]
+    fn profile_param_sets_foundry_profile_env() {
+        let params = json!({
+            "script": "script/Deploy.s.sol",
+            "rpc_url": "https://rpc.example.com",
+            "profile": "ci"
+        });
+
+        let env = foundry_profile_env(&params).unwrap();
+        assert_eq!(env.get("FOUNDRY_PROFILE").map(String::as_str), Some("ci"));
+        // The profile is passed via the environment, never as a CLI flag.
+        assert!(!build_args(&params).unwrap().contains(&"ci".to_string()));
+    }
+
+    #[test]
+    fn parse_returns_maps_multiple_values_to_typed_entries() {
+        let output = "\
+Compiling 1 files with Solc 0.8.24
+Script ran successfully.
+Gas used: 123456
+
+== Return ==
+deployed: address 0x5FbDB2315678afecb367f032d93F642f64180aa3
+1: uint256 1000000000000000000 [1e18]
+ok: bool true
+label: string \"hello, world\"
+ids: uint256[] [1, 2, 3]
+
+== Logs ==
+  deployed
+";
+
+        assert_eq!(
+            parse_returns(output),
+            vec![
+                json!({
+                    "index": 0,
+                    "name": "deployed",
+                    "type": "address",
+                    "value": "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+                }),
+                json!({
+                    "index": 1,
+                    "name": null,
+                    "type": "uint256",
+                    "value": "1000000000000000000"
+                }),
+                json!({ "index": 2, "name": "ok", "type": "bool", "value": true }),
+                json!({
+                    "index": 3,
+                    "name": "label",
+                    "type": "string",
+                    "value": "hello, world"
+                }),
+                json!({
+                    "index": 4,
+                    "name": "ids",
+                    "type": "uint256[]",
+                    "value": ["1", "2", "3"]
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_returns_handles_nested_tuples_and_arrays() {
+        let output = "\
+== Return ==
+0: struct Deploy.Pool[] [(0x0000000000000000000000000000000000000001, [1, 2]), (0x0000000000000000000000000000000000000002, [])]
+1: address[2][] [[0x0000000000000000000000000000000000000003, 0x0000000000000000000000000000000000000004]]
+";
+
+        let returns = parse_returns(output);
+        assert_eq!(returns[0]["type"], "struct Deploy.Pool[]");
+        assert_eq!(
+            returns[0]["value"],
+            json!([
+                ["0x0000000000000000000000000000000000000001", ["1", "2"]],
+                ["0x0000000000000000000000000000000000000002", []]
+            ])
+        );
+        assert_eq!(returns[1]["type"], "address[2][]");
+        assert_eq!(
+            returns[1]["value"],
+            json!([[
+                "0x0000000000000000000000000000000000000003",
+                "0x0000000000000000000000000000000000000004"
+            ]])
+        );
+    }
+
+    #[test]
+    fn parse_returns_is_empty_without_return_section() {
+        assert!(parse_returns("Script ran successfully.\nGas used: 1\n").is_empty());
+    }
+
+    #[test]
+    fn parse_deployments_extracts_created_contracts() {
+        let artifact = json!({
+            "transactions": [
+                {
+                    "hash": "0xaaa1",
+                    "transactionType": "CREATE",
+                    "contractName": "Token",
+                    "contractAddress": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "additionalContracts": []
+                },
+                {
+                    "hash": "0xbbb2",
+                    "transactionType": "CALL",
+                    "contractName": "Token",
+                    "contractAddress": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "function": "mint(address,uint256)"
+                },
+                {
+                    "hash": "0xccc3",
+                    "transactionType": "CREATE2",
+                    "contractName": "Factory",
+                    "contractAddress": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512",
+                    "additionalContracts": [
+                        {
+                            "transactionType": "CREATE",
+                            "address": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0",
+                            "initCode": "0x6080"
+                        }
+                    ]
+                }
+            ],
+            "receipts": [],
+            "chain": 31337
+        });
+
+        let deployments = parse_deployments(&artifact.to_string()).unwrap();
+        assert_eq!(
+            deployments,
+            vec![
+                json!({
+                    "contract_name": "Token",
+                    "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "tx_hash": "0xaaa1"
+                }),
+                json!({
+                    "contract_name": "Factory",
+                    "address": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512",
+                    "tx_hash": "0xccc3"
+                }),
+                json!({
+                    "contract_name": null,
+                    "address": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0",
+                    "tx_hash": "0xccc3"
+                }),
+            ]
+        );
+
+        assert!(parse_deployments("{}").is_err());
+        assert!(parse_deployments("not json").is_err());
+    }
+
+    #[test]
+    fn broadcast_location_follows_forge_layout() {
+        let params = json!({ "script": "script/Deploy.s.sol:DeployScript" });
+        assert_eq!(
+            broadcast_location(&params).unwrap(),
+            (
+                "broadcast/Deploy.s.sol".to_string(),
+                "run-latest.json".to_string()
+            )
+        );
+
+        let params = json!({ "script": "script/Deploy.s.sol", "sig": "deploy(uint256)" });
+        assert_eq!(broadcast_location(&params).unwrap().1, "deploy-latest.json");
+
+        let params = json!({ "script": "script/", "sig": "run()" });
+        assert!(broadcast_location(&params).is_err());
+    }
+
     #[test]
     fn schema_is_valid_json() {
         let schema_str = ForgeScriptTool::schema();
@@ -0,0 +1,375 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use wasm_tools_common::{parse_params, typed_params};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+use near::agent::host;
+
+/// Keeps a single batch well under typical provider batch limits.
+const MAX_CALLS: usize = 50;
+const REQUEST_TIMEOUT_MS: u32 = 30_000;
+
+struct JsonRpcBatchTool;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonRpcBatchParams {
+    rpc_url: String,
+    calls: Vec<RpcCall>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RpcCall {
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+impl Guest for JsonRpcBatchTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "json_rpc_batch",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "rpc_url": {
+                    "type": "string",
+                    "description": "JSON-RPC endpoint URL. Its host must be in the tool's http allowlist."
+                },
+                "calls": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": MAX_CALLS,
+                    "description": "Calls to send in one batch; results come back in the same order",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "method": {
+                                "type": "string",
+                                "description": "JSON-RPC method, e.g. 'eth_getBalance'"
+                            },
+                            "params": {
+                                "type": ["array", "object"],
+                                "description": "Method params (default: [])"
+                            }
+                        },
+                        "required": ["method"]
+                    }
+                }
+            },
+            "required": ["rpc_url", "calls"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Send several JSON-RPC calls (e.g. eth_getBalance, eth_getTransactionCount, \
+         eth_gasPrice) to an endpoint as a single batch request and return each call's \
+         result or error, in order. Uses HTTP only; nothing is signed."
+            .to_string()
+    }
+}
+
+export!(JsonRpcBatchTool);
+
+fn execute_impl(params_raw: &str) -> Result<String, String> {
+    let params: JsonRpcBatchParams = typed_params(&parse_params(params_raw)?)?;
+
+    run_batch(&params, |url, body| {
+        let response = host::http_request(
+            "POST",
+            url,
+            r#"{"Content-Type":"application/json"}"#,
+            Some(body),
+            Some(REQUEST_TIMEOUT_MS),
+        )
+        .map_err(|err| format!("http request failed: {err}"))?;
+        Ok((response.status, response.body))
+    })
+}
+
+/// Sends the batch through `post` (url, body) -> (status, body) and
+/// matches the responses back to the calls.
+fn run_batch(
+    params: &JsonRpcBatchParams,
+    post: impl FnOnce(&str, &[u8]) -> Result<(u16, Vec<u8>), String>,
+) -> Result<String, String> {
+    let request = build_batch_request(&params.calls)?;
+    let body = serde_json::to_vec(&request).map_err(|err| format!("request encode: {err}"))?;
+
+    let (status, response) = post(&params.rpc_url, &body)?;
+    let response = String::from_utf8_lossy(&response);
+
+    if !(200..300).contains(&status) {
+        return Err(format!(
+            "endpoint returned HTTP {}: {}",
+            status,
+            response.trim()
+        ));
+    }
+
+    let results = demux_batch_response(&params.calls, &response)?;
+    let all_succeeded = results.iter().all(|result| result.get("error").is_none());
+
+    Ok(json!({
+        "rpc_url": params.rpc_url,
+        "all_succeeded": all_succeeded,
+        "results": results
+    })
+    .to_string())
+}
+
+/// One request object per call, with the call's index as its id.
+fn build_batch_request(calls: &[RpcCall]) -> Result<Value, String> {
+    if calls.is_empty() {
+        return Err("'calls' must contain at least one call".to_string());
+    }
+
+    if calls.len() > MAX_CALLS {
+        return Err(format!(
+            "'calls' has {} entries; at most {} are allowed",
+            calls.len(),
+            MAX_CALLS
+        ));
+    }
+
+    calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| {
+            if call.method.trim().is_empty() {
+                return Err(format!("calls[{index}]: 'method' must not be empty"));
+            }
+
+            let params = match &call.params {
+                None => json!([]),
+                Some(params @ (Value::Array(_) | Value::Object(_))) => params.clone(),
+                Some(_) => {
+                    return Err(format!(
+                        "calls[{index}]: 'params' must be an array or object"
+                    ));
+                }
+            };
+
+            Ok(json!({
+                "jsonrpc": "2.0",
+                "id": index,
+                "method": call.method,
+                "params": params
+            }))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+/// Batch responses may come back in any order, so they are matched to calls
+/// by id. A call with no response is reported as an error rather than
+/// failing the whole batch.
+fn demux_batch_response(calls: &[RpcCall], raw: &str) -> Result<Vec<Value>, String> {
+    let response: Value =
+        serde_json::from_str(raw).map_err(|err| format!("invalid JSON-RPC response: {err}"))?;
+
+    let entries = match response {
+        Value::Array(entries) => entries,
+        // Endpoints that reject the batch as a whole answer with one error.
+        single if single.get("error").is_some() => {
+            return Err(format!("endpoint rejected the batch: {}", single["error"]));
+        }
+        _ => return Err("endpoint did not return a batch response".to_string()),
+    };
+
+    let mut by_id: Vec<Option<Value>> = vec![None; calls.len()];
+    for entry in entries {
+        if let Some(index) = entry["id"].as_u64().map(|id| id as usize)
+            && index < by_id.len()
+        {
+            by_id[index] = Some(entry);
+        }
+    }
+
+    Ok(calls
+        .iter()
+        .zip(by_id)
+        .enumerate()
+        .map(|(index, (call, entry))| {
+            let mut result = json!({ "index": index, "method": call.method });
+            match entry {
+                Some(entry) if entry.get("error").is_some_and(|err| !err.is_null()) => {
+                    result["error"] = entry["error"].clone();
+                }
+                Some(entry) => result["result"] = entry["result"].clone(),
+                None => result["error"] = json!({ "message": "no response for this call" }),
+            }
+            result
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "0x000000000000000000000000000000000000dEaD";
+
+    fn batch_params(params: Value) -> JsonRpcBatchParams {
+        typed_params(&params).unwrap()
+    }
+
+    fn wallet_reads() -> JsonRpcBatchParams {
+        batch_params(json!({
+            "rpc_url": "http://127.0.0.1:8545",
+            "calls": [
+                { "method": "eth_getBalance", "params": [ACCOUNT, "latest"] },
+                { "method": "eth_getTransactionCount", "params": [ACCOUNT, "latest"] },
+                { "method": "eth_gasPrice" }
+            ]
+        }))
+    }
+
+    #[test]
+    fn build_batch_request_numbers_calls_by_index() {
+        let request = build_batch_request(&wallet_reads().calls).unwrap();
+        assert_eq!(
+            request,
+            json!([
+                {
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "method": "eth_getBalance",
+                    "params": [ACCOUNT, "latest"]
+                },
+                {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "eth_getTransactionCount",
+                    "params": [ACCOUNT, "latest"]
+                },
+                { "jsonrpc": "2.0", "id": 2, "method": "eth_gasPrice", "params": [] }
+            ])
+        );
+    }
+
+    #[test]
+    fn build_batch_request_validates_calls() {
+        let params = batch_params(json!({ "rpc_url": "http://x", "calls": [] }));
+        assert!(build_batch_request(&params.calls).is_err());
+
+        let params = batch_params(json!({
+            "rpc_url": "http://x",
+            "calls": vec![json!({ "method": "eth_chainId" }); MAX_CALLS + 1]
+        }));
+        assert!(
+            build_batch_request(&params.calls)
+                .unwrap_err()
+                .contains("at most 50")
+        );
+
+        let params = batch_params(json!({
+            "rpc_url": "http://x",
+            "calls": [{ "method": "eth_chainId" }, { "method": "eth_call", "params": "0x" }]
+        }));
+        assert_eq!(
+            build_batch_request(&params.calls).unwrap_err(),
+            "calls[1]: 'params' must be an array or object"
+        );
+    }
+
+    #[test]
+    fn run_batch_demultiplexes_out_of_order_responses() {
+        let params = wallet_reads();
+
+        let output = run_batch(&params, |url, body| {
+            assert_eq!(url, "http://127.0.0.1:8545");
+            let sent: Value = serde_json::from_slice(body).unwrap();
+            assert_eq!(sent.as_array().unwrap().len(), 3);
+
+            let response = json!([
+                { "jsonrpc": "2.0", "id": 2, "result": "0x3b9aca00" },
+                {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32000, "message": "header not found" }
+                },
+                { "jsonrpc": "2.0", "id": 0, "result": "0xde0b6b3a7640000" }
+            ]);
+            Ok((200, response.to_string().into_bytes()))
+        })
+        .unwrap();
+
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["all_succeeded"], false);
+        assert_eq!(
+            output["results"],
+            json!([
+                { "index": 0, "method": "eth_getBalance", "result": "0xde0b6b3a7640000" },
+                {
+                    "index": 1,
+                    "method": "eth_getTransactionCount",
+                    "error": { "code": -32000, "message": "header not found" }
+                },
+                { "index": 2, "method": "eth_gasPrice", "result": "0x3b9aca00" }
+            ])
+        );
+    }
+
+    #[test]
+    fn demux_reports_missing_responses_per_call() {
+        let params = wallet_reads();
+        let results = demux_batch_response(
+            &params.calls,
+            r#"[{"jsonrpc":"2.0","id":0,"result":"0x1"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(results[0]["result"], "0x1");
+        assert_eq!(results[1]["error"]["message"], "no response for this call");
+        assert_eq!(results[2]["error"]["message"], "no response for this call");
+    }
+
+    #[test]
+    fn run_batch_surfaces_batch_level_failures() {
+        let params = wallet_reads();
+
+        let err = run_batch(&params, |_, _| Ok((429, b"rate limited".to_vec()))).unwrap_err();
+        assert_eq!(err, "endpoint returned HTTP 429: rate limited");
+
+        let err = run_batch(&params, |_, _| {
+            Ok((
+                200,
+                br#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"batch not supported"}}"#
+                    .to_vec(),
+            ))
+        })
+        .unwrap_err();
+        assert!(err.starts_with("endpoint rejected the batch: "), "{err}");
+        assert!(err.contains("batch not supported"), "{err}");
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema: Value = serde_json::from_str(&JsonRpcBatchTool::schema()).expect("valid JSON");
+        assert_eq!(schema["title"], "json_rpc_batch");
+        assert_eq!(schema["required"], json!(["rpc_url", "calls"]));
+        assert_eq!(schema["properties"]["calls"]["maxItems"], MAX_CALLS);
+    }
+}
@@ -27,6 +27,7 @@ impl Guest for JsonTransformTool {
     fn schema() -> String {
         json!({
             "title": "json_transform",
+            "x-effects": "pure",
             "type": "object",
             "additionalProperties": false,
             "properties": {
@@ -42,11 +43,24 @@ impl Guest for JsonTransformTool {
                     "items": {"type": "string"},
                     "description": "Optional top-level keys to retain when input is an object"
                 },
+                "map_pick": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Optional top-level keys to retain in each element when input is an array of objects"
+                },
+                "filter_has": {
+                    "type": "string",
+                    "description": "Optional key; when input is an array, keep only object elements that contain it"
+                },
                 "set": {
                     "type": "object",
                     "additionalProperties": true,
                     "description": "Optional top-level fields to set/overwrite when input is an object"
                 },
+                "extract": {
+                    "type": "string",
+                    "description": "Optional JSON Pointer (RFC 6901, e.g. `/result/receipt/status`); the output is only the value it points to"
+                },
                 "pretty": {
                     "type": "boolean",
                     "default": false,
@@ -74,6 +88,14 @@ fn execute_impl(params_raw: &str) -> Result<String, String> {
 
     let mut value = extract_input_value(&params)?;
 
+    if let Some(filter_value) = params.get("filter_has") {
+        apply_filter_has(&mut value, filter_value)?;
+    }
+
+    if let Some(map_pick_value) = params.get("map_pick") {
+        apply_map_pick(&mut value, map_pick_value)?;
+    }
+
     if let Some(pick_value) = params.get("pick") {
         apply_pick(&mut value, pick_value)?;
     }
@@ -82,6 +104,10 @@ fn execute_impl(params_raw: &str) -> Result<String, String> {
         apply_set(&mut value, set_value)?;
     }
 
+    if let Some(extract_value) = params.get("extract") {
+        value = apply_extract(value, extract_value)?;
+    }
+
     let pretty = params
         .get("pretty")
         .and_then(Value::as_bool)
@@ -112,31 +138,77 @@ fn extract_input_value(params: &Value) -> Result<Value, String> {
 }
 
 fn apply_pick(value: &mut Value, pick_value: &Value) -> Result<(), String> {
-    let pick_keys = pick_value
-        .as_array()
-        .ok_or_else(|| "`pick` must be an array of strings".to_string())?;
+    let pick_keys = string_keys(pick_value, "pick")?;
 
     let object = value
         .as_object()
         .ok_or_else(|| "`pick` can only be used when input is a JSON object".to_string())?;
 
-    let mut filtered = Map::new();
+    *value = Value::Object(pick_from(object, &pick_keys));
 
-    for key_value in pick_keys {
-        let key = key_value
-            .as_str()
-            .ok_or_else(|| "`pick` must contain only strings".to_string())?;
+    Ok(())
+}
 
-        if let Some(existing) = object.get(key) {
-            filtered.insert(key.to_string(), existing.clone());
-        }
+fn apply_map_pick(value: &mut Value, map_pick_value: &Value) -> Result<(), String> {
+    let pick_keys = string_keys(map_pick_value, "map_pick")?;
+
+    let elements = value
+        .as_array_mut()
+        .ok_or_else(|| "`map_pick` can only be used when input is a JSON array".to_string())?;
+
+    for (index, element) in elements.iter_mut().enumerate() {
+        let object = element.as_object().ok_or_else(|| {
+            format!(
+                "`map_pick` requires every array element to be an object (element {index} is not)"
+            )
+        })?;
+        *element = Value::Object(pick_from(object, &pick_keys));
     }
 
-    *value = Value::Object(filtered);
+    Ok(())
+}
+
+fn apply_filter_has(value: &mut Value, filter_value: &Value) -> Result<(), String> {
+    let key = filter_value
+        .as_str()
+        .ok_or_else(|| "`filter_has` must be a string".to_string())?;
+
+    let elements = value
+        .as_array_mut()
+        .ok_or_else(|| "`filter_has` can only be used when input is a JSON array".to_string())?;
+
+    elements.retain(|element| {
+        element
+            .as_object()
+            .is_some_and(|object| object.contains_key(key))
+    });
 
     Ok(())
 }
 
+fn string_keys<'a>(keys_value: &'a Value, param: &str) -> Result<Vec<&'a str>, String> {
+    keys_value
+        .as_array()
+        .ok_or_else(|| format!("`{param}` must be an array of strings"))?
+        .iter()
+        .map(|key_value| {
+            key_value
+                .as_str()
+                .ok_or_else(|| format!("`{param}` must contain only strings"))
+        })
+        .collect()
+}
+
+fn pick_from(object: &Map<String, Value>, keys: &[&str]) -> Map<String, Value> {
+    keys.iter()
+        .filter_map(|key| {
+            object
+                .get(*key)
+                .map(|existing| (key.to_string(), existing.clone()))
+        })
+        .collect()
+}
+
 fn apply_set(value: &mut Value, set_value: &Value) -> Result<(), String> {
     let set_object = set_value
         .as_object()
@@ -152,3 +224,97 @@ fn apply_set(value: &mut Value, set_value: &Value) -> Result<(), String> {
 
     Ok(())
 }
+
+fn apply_extract(mut value: Value, extract_value: &Value) -> Result<Value, String> {
+    let pointer = extract_value
+        .as_str()
+        .ok_or_else(|| "`extract` must be a JSON Pointer string".to_string())?;
+
+    value
+        .pointer_mut(pointer)
+        .map(Value::take)
+        .ok_or_else(|| format!("`extract` pointer `{pointer}` does not resolve in the input"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(params: Value) -> Result<String, String> {
+        execute_impl(&params.to_string())
+    }
+
+    #[test]
+    fn map_pick_applies_pick_to_each_element() {
+        let input = json!([
+            { "hash": "0xa", "status": "0x1", "logs": [] },
+            { "hash": "0xb", "logs": [1] }
+        ]);
+        let output = run(json!({ "input": input, "map_pick": ["hash", "status"] })).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&output).unwrap(),
+            json!([{ "hash": "0xa", "status": "0x1" }, { "hash": "0xb" }])
+        );
+
+        let err = run(json!({ "input": [{ "a": 1 }, 2], "map_pick": ["a"] })).unwrap_err();
+        assert!(err.contains("element 1"), "{err}");
+    }
+
+    #[test]
+    fn filter_has_keeps_elements_containing_key() {
+        let input = json!([{ "error": "reverted" }, { "result": "0x1" }, "raw", { "error": null }]);
+        let output = run(json!({ "input": input, "filter_has": "error" })).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&output).unwrap(),
+            json!([{ "error": "reverted" }, { "error": null }])
+        );
+
+        let params = json!({ "input": input, "filter_has": "error", "map_pick": ["error"] });
+        let output = run(params).unwrap();
+        assert_eq!(output, r#"[{"error":"reverted"},{"error":null}]"#);
+    }
+
+    #[test]
+    fn array_operations_reject_non_array_input() {
+        let err = run(json!({ "input": { "a": 1 }, "map_pick": ["a"] })).unwrap_err();
+        assert!(err.contains("`map_pick` can only be used"), "{err}");
+
+        let err = run(json!({ "input": { "a": 1 }, "filter_has": "a" })).unwrap_err();
+        assert!(err.contains("`filter_has` can only be used"), "{err}");
+    }
+
+    #[test]
+    fn extract_returns_value_at_pointer() {
+        let input = json!({ "result": { "receipt": { "status": "0x1", "logs": [] } } });
+        let output = run(json!({ "input": input, "extract": "/result/receipt/status" })).unwrap();
+        assert_eq!(output, r#""0x1""#);
+
+        let output = run(json!({ "input": input, "extract": "" })).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&output).unwrap(), input);
+    }
+
+    #[test]
+    fn extract_errors_when_pointer_does_not_resolve() {
+        let input = json!({ "result": { "receipt": null } });
+        let err = run(json!({ "input": input, "extract": "/result/receipt/status" })).unwrap_err();
+        assert!(err.contains("/result/receipt/status"), "{err}");
+
+        let err = run(json!({ "input": input, "extract": "result" })).unwrap_err();
+        assert!(err.contains("does not resolve"), "{err}");
+
+        let err = run(json!({ "input": input, "extract": 1 })).unwrap_err();
+        assert!(err.contains("JSON Pointer string"), "{err}");
+    }
+
+    #[test]
+    fn extract_runs_after_set_and_before_pretty() {
+        let params = json!({
+            "input": { "a": 1 },
+            "set": { "b": { "c": [1, 2] } },
+            "extract": "/b",
+            "pretty": true
+        });
+        let output = run(params).unwrap();
+        assert_eq!(output, "{\n  \"c\": [\n    1,\n    2\n  ]\n}");
+    }
+}
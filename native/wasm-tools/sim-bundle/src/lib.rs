@@ -0,0 +1,414 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+use wasm_tools_common::{apply_context_chain, parse_params, typed_params, validate_address};
+
+wit_bindgen::generate!({
+    path: "../../lemon-wasm-runtime/wit",
+    world: "sandboxed-tool",
+});
+
+use exports::near::agent::tool::{Guest, Request, Response};
+use near::agent::host;
+
+/// Stays under the exec rate limit in `sim_bundle.capabilities.json`.
+const MAX_TRANSACTIONS: usize = 25;
+const CALL_TIMEOUT_MS: u32 = 30_000;
+
+struct SimBundleTool;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SimBundleParams {
+    fork_url: String,
+    transactions: Vec<BundleTransaction>,
+    chain: Option<String>,
+    block: Option<String>,
+    #[serde(default = "default_stop_on_revert")]
+    stop_on_revert: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BundleTransaction {
+    to: String,
+    sig: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    from: Option<String>,
+    value: Option<String>,
+}
+
+fn default_stop_on_revert() -> bool {
+    true
+}
+
+impl Guest for SimBundleTool {
+    fn execute(req: Request) -> Response {
+        match execute_impl(&req.params, req.context.as_deref()) {
+            Ok(output) => Response {
+                output: Some(output),
+                error: None,
+            },
+            Err(error) => Response {
+                output: None,
+                error: Some(error),
+            },
+        }
+    }
+
+    fn schema() -> String {
+        json!({
+            "title": "sim_bundle",
+            "x-effects": "readonly",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "fork_url": {
+                    "type": "string",
+                    "description": "JSON-RPC endpoint of the fork to simulate against (e.g. a local Anvil fork)"
+                },
+                "transactions": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": MAX_TRANSACTIONS,
+                    "description": "Transactions to simulate, in order",
+                    "items": {
+                        "type": "object",
+                        "additionalProperties": false,
+                        "properties": {
+                            "to": {
+                                "type": "string",
+                                "description": "Recipient address (0x-prefixed hex)"
+                            },
+                            "sig": {
+                                "type": "string",
+                                "description": "Function signature, e.g. \"approve(address,uint256)\""
+                            },
+                            "args": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Arguments to the function call"
+                            },
+                            "from": {
+                                "type": "string",
+                                "description": "Sender address to simulate the call from"
+                            },
+                            "value": {
+                                "type": "string",
+                                "description": "ETH value to send (in wei or with units like '1ether')"
+                            }
+                        },
+                        "required": ["to"]
+                    }
+                },
+                "chain": {
+                    "type": "string",
+                    "description": "Chain name or ID (e.g. 'mainnet', '1', 'sepolia'). Defaults to the invoke context's chain_id when omitted."
+                },
+                "block": {
+                    "type": "string",
+                    "description": "Block number or tag to simulate at (e.g. 'latest', 'pending', a number)"
+                },
+                "stop_on_revert": {
+                    "type": "boolean",
+                    "description": "Skip the remaining transactions after the first revert (default: true)"
+                }
+            },
+            "required": ["fork_url", "transactions"]
+        })
+        .to_string()
+    }
+
+    fn description() -> String {
+        "Simulate an ordered bundle of transactions against a fork with `cast call`, \
+         reporting success or revert per transaction. Nothing is signed or broadcast. \
+         Each call runs against the fork's current state, so effects of earlier \
+         transactions are not carried into later ones."
+            .to_string()
+    }
+}
+
+export!(SimBundleTool);
+
+fn execute_impl(params_raw: &str, context_raw: Option<&str>) -> Result<String, String> {
+    let mut params = parse_params(params_raw)?;
+    apply_context_chain(&mut params, context_raw);
+
+    let params: SimBundleParams = typed_params(&params)?;
+    let calls = build_bundle_args(&params)?;
+
+    let mut results = Vec::with_capacity(calls.len());
+    let mut reverted = false;
+
+    for (index, args) in calls.iter().enumerate() {
+        if reverted && params.stop_on_revert {
+            results.push(json!({ "index": index, "status": "skipped" }));
+            continue;
+        }
+
+        let result = simulate_call(args)?;
+        reverted |= result["status"] == "reverted";
+        results.push(with_index(result, index));
+    }
+
+    Ok(json!({
+        "fork_url": params.fork_url,
+        "all_succeeded": !reverted,
+        "results": results
+    })
+    .to_string())
+}
+
+/// Runs one `cast call`. A non-zero exit is a revert and is reported in the
+/// per-tx result; only a host-side exec failure aborts the bundle.
+fn simulate_call(args: &[String]) -> Result<Value, String> {
+    let args_json = serde_json::to_string(args).map_err(|err| format!("args encode: {err}"))?;
+
+    let result = host::exec_command("cast", &args_json, "{}", Some(CALL_TIMEOUT_MS), None)
+        .map_err(|err| format!("exec failed: {err}"))?;
+
+    if result.exit_code != 0 {
+        let stderr = result.stderr.trim();
+        return Ok(json!({
+            "status": "reverted",
+            "error": if stderr.is_empty() { result.stdout.trim() } else { stderr }
+        }));
+    }
+
+    Ok(json!({
+        "status": "success",
+        "output": result.stdout.trim()
+    }))
+}
+
+fn with_index(mut result: Value, index: usize) -> Value {
+    result["index"] = json!(index);
+    result
+}
+
+/// Builds every call up front so a bad address late in the bundle fails
+/// before anything runs.
+fn build_bundle_args(params: &SimBundleParams) -> Result<Vec<Vec<String>>, String> {
+    if params.transactions.is_empty() {
+        return Err("'transactions' must contain at least one transaction".to_string());
+    }
+
+    if params.transactions.len() > MAX_TRANSACTIONS {
+        return Err(format!(
+            "'transactions' has {} entries; at most {} are allowed",
+            params.transactions.len(),
+            MAX_TRANSACTIONS
+        ));
+    }
+
+    params
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            build_call_args(tx, params).map_err(|err| format!("transactions[{index}]: {err}"))
+        })
+        .collect()
+}
+
+fn build_call_args(
+    tx: &BundleTransaction,
+    params: &SimBundleParams,
+) -> Result<Vec<String>, String> {
+    validate_address(&tx.to)?;
+
+    let mut args: Vec<String> = vec!["call".to_string(), tx.to.clone()];
+
+    if let Some(sig) = &tx.sig {
+        args.push(sig.clone());
+        args.extend(tx.args.iter().cloned());
+    } else if !tx.args.is_empty() {
+        return Err("'args' requires 'sig'".to_string());
+    }
+
+    if let Some(from) = &tx.from {
+        validate_address(from)?;
+        args.push("--from".to_string());
+        args.push(from.clone());
+    }
+
+    if let Some(value) = &tx.value {
+        args.push("--value".to_string());
+        args.push(value.clone());
+    }
+
+    args.push("--rpc-url".to_string());
+    args.push(params.fork_url.clone());
+
+    if let Some(chain) = &params.chain {
+        args.push("--chain".to_string());
+        args.push(chain.clone());
+    }
+
+    if let Some(block) = &params.block {
+        args.push("--block".to_string());
+        args.push(block.clone());
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0x1234567890abcdef1234567890abcdef12345678";
+    const ROUTER: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    const USER: &str = "0x000000000000000000000000000000000000dEaD";
+
+    fn bundle_params(params: Value) -> Result<SimBundleParams, String> {
+        typed_params(&params)
+    }
+
+    #[test]
+    fn build_bundle_args_preserves_transaction_order() {
+        let params = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": [
+                {
+                    "to": TOKEN,
+                    "sig": "approve(address,uint256)",
+                    "args": [ROUTER, "1000"],
+                    "from": USER
+                },
+                {
+                    "to": ROUTER,
+                    "sig": "swap(uint256)",
+                    "args": ["1000"],
+                    "from": USER,
+                    "value": "1ether"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let calls = build_bundle_args(&params).unwrap();
+        assert_eq!(
+            calls,
+            vec![
+                vec![
+                    "call",
+                    TOKEN,
+                    "approve(address,uint256)",
+                    ROUTER,
+                    "1000",
+                    "--from",
+                    USER,
+                    "--rpc-url",
+                    "http://127.0.0.1:8545"
+                ],
+                vec![
+                    "call",
+                    ROUTER,
+                    "swap(uint256)",
+                    "1000",
+                    "--from",
+                    USER,
+                    "--value",
+                    "1ether",
+                    "--rpc-url",
+                    "http://127.0.0.1:8545"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn bundle_level_chain_and_block_apply_to_every_call() {
+        let mut raw = json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "block": "19000000",
+            "transactions": [{ "to": TOKEN }, { "to": ROUTER, "value": "1" }]
+        });
+        apply_context_chain(&mut raw, Some(r#"{"chain_id":1}"#));
+
+        let calls = build_bundle_args(&bundle_params(raw).unwrap()).unwrap();
+        for call in &calls {
+            assert_eq!(
+                call[call.len() - 4..],
+                ["--chain", "1", "--block", "19000000"]
+            );
+        }
+        assert_eq!(calls[0][..2], ["call", TOKEN]);
+        assert_eq!(calls[1][..2], ["call", ROUTER]);
+    }
+
+    #[test]
+    fn build_bundle_args_reports_failing_transaction_index() {
+        let params = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": [{ "to": TOKEN }, { "to": "not_an_address" }]
+        }))
+        .unwrap();
+
+        let err = build_bundle_args(&params).unwrap_err();
+        assert!(err.starts_with("transactions[1]: "), "{err}");
+
+        let params = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": [{ "to": TOKEN, "args": ["1"] }]
+        }))
+        .unwrap();
+        assert_eq!(
+            build_bundle_args(&params).unwrap_err(),
+            "transactions[0]: 'args' requires 'sig'"
+        );
+    }
+
+    #[test]
+    fn build_bundle_args_bounds_transaction_count() {
+        let empty = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": []
+        }))
+        .unwrap();
+        assert!(build_bundle_args(&empty).is_err());
+
+        let too_many = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": vec![json!({ "to": TOKEN }); MAX_TRANSACTIONS + 1]
+        }))
+        .unwrap();
+        assert!(
+            build_bundle_args(&too_many)
+                .unwrap_err()
+                .contains("at most 25")
+        );
+    }
+
+    #[test]
+    fn stop_on_revert_defaults_to_true() {
+        let params = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": [{ "to": TOKEN }]
+        }))
+        .unwrap();
+        assert!(params.stop_on_revert);
+    }
+
+    #[test]
+    fn typed_params_reject_signing_fields() {
+        let err = bundle_params(json!({
+            "fork_url": "http://127.0.0.1:8545",
+            "transactions": [{ "to": TOKEN, "private_key": "0x01" }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("unknown field `private_key`"), "{err}");
+    }
+
+    #[test]
+    fn schema_is_valid_json() {
+        let schema: Value = serde_json::from_str(&SimBundleTool::schema()).expect("valid JSON");
+        assert_eq!(schema["title"], "sim_bundle");
+        assert_eq!(schema["required"], json!(["fork_url", "transactions"]));
+        assert_eq!(
+            schema["properties"]["transactions"]["maxItems"],
+            MAX_TRANSACTIONS
+        );
+    }
+}